@@ -0,0 +1,47 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use mosers::{Language, MosesPunctNormalizer};
+use std::hint::black_box;
+
+pub fn normalizer_benchmark(c: &mut Criterion) {
+    let texts = [
+        (
+            "The United States in 1805 (color map)        _Facing_ 193",
+            Language::En,
+            "normalize_whitespace",
+        ),
+        (
+            "«Les «fondateurs» sont là»",
+            Language::Fr,
+            "normalize_french_quotes",
+        ),
+        (
+            "Am 1\u{a0}000 Tag sagte er: „Hallo…“, dann ging er.",
+            Language::De,
+            "normalize_de_quotes_numbers",
+        ),
+        (
+            "5\u{a0}% of the ``users'' said “it’s fine”, didn't they?",
+            Language::En,
+            "normalize_penn_quotes",
+        ),
+        (
+            "０１２３４５６７８９，？！",
+            Language::Zh,
+            "normalize_unicode_punct",
+        ),
+    ];
+
+    for (text, lang, name) in texts {
+        let normalizer = MosesPunctNormalizer::new(lang);
+        black_box(normalizer.normalize(text));
+
+        c.bench_function(name, |b| {
+            b.iter(|| {
+                black_box(normalizer.normalize(text));
+            })
+        });
+    }
+}
+
+criterion_group!(benches, normalizer_benchmark);
+criterion_main!(benches);
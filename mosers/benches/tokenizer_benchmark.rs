@@ -1,5 +1,6 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, Criterion};
 use mosers::{Language, MosesTokenizer};
+use std::hint::black_box;
 
 pub fn tokenizer_benchmark(c: &mut Criterion) {
     let texts = [
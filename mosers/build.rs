@@ -0,0 +1,82 @@
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+const PERLUNIPROPS: &str = "data/perluniprops";
+
+/// Sorted, non-overlapping inclusive ranges of the characters in `chars`.
+fn ranges(chars: &str) -> Vec<(char, char)> {
+    // Newlines separate entries in some of the data files
+    let mut chars: Vec<char> = chars.chars().filter(|c| *c != '\n').collect();
+    chars.sort_unstable();
+    chars.dedup();
+
+    let mut ranges: Vec<(char, char)> = Vec::new();
+    for c in chars {
+        match ranges.last_mut() {
+            Some((_, end)) if *end as u32 + 1 == c as u32 => *end = c,
+            _ => ranges.push((c, c)),
+        }
+    }
+
+    ranges
+}
+
+/// The body of a regex character class matching `ranges`.
+fn class(ranges: &[(char, char)]) -> String {
+    let mut class = String::new();
+    for (start, end) in ranges {
+        write!(class, "\\x{{{:X}}}", *start as u32).unwrap();
+        if start != end {
+            write!(class, "-\\x{{{:X}}}", *end as u32).unwrap();
+        }
+    }
+
+    class
+}
+
+/// Turns the character lists of `data/perluniprops` into range tables and
+/// regex classes, so that neither has to be built from the lists at runtime.
+fn main() {
+    println!("cargo:rerun-if-changed={}", PERLUNIPROPS);
+
+    let mut entries: Vec<_> = fs::read_dir(PERLUNIPROPS)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    entries.sort();
+
+    let mut tables = String::new();
+    for path in entries {
+        let name = path
+            .file_stem()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_uppercase()
+            .replace('-', "_");
+        let ranges = ranges(&fs::read_to_string(&path).unwrap());
+
+        write!(
+            tables,
+            "pub(crate) const {}_RANGES: &[(char, char)] = &[",
+            name
+        )
+        .unwrap();
+        for (start, end) in &ranges {
+            write!(tables, "({:?}, {:?}),", start, end).unwrap();
+        }
+        writeln!(tables, "];").unwrap();
+        writeln!(
+            tables,
+            "pub(crate) const {}_CLASS: &str = {:?};",
+            name,
+            class(&ranges)
+        )
+        .unwrap();
+    }
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("perluniprops.rs"), tables).unwrap();
+}
@@ -0,0 +1,72 @@
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{MosesPunctNormalizer, MosesTokenizer, Tokens};
+
+/// Maps `f` over `items` keeping their order, across all cores when the
+/// `parallel` feature is enabled.
+#[cfg(feature = "parallel")]
+fn map_ordered<S, T, F>(items: &[S], f: F) -> Vec<T>
+where
+    S: Sync,
+    T: Send,
+    F: Fn(&S) -> T + Sync + Send,
+{
+    items.par_iter().map(f).collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+fn map_ordered<S, T, F>(items: &[S], f: F) -> Vec<T>
+where
+    F: Fn(&S) -> T,
+{
+    items.iter().map(f).collect()
+}
+
+impl MosesTokenizer {
+    /// Tokenizes every text of `texts`, results are in the same order.
+    pub fn tokenize_batch<S: AsRef<str> + Sync>(
+        &self,
+        texts: &[S],
+        escape: Option<bool>,
+    ) -> Vec<Tokens> {
+        map_ordered(texts, |text| self.tokenize(text.as_ref(), escape))
+    }
+}
+
+impl MosesPunctNormalizer {
+    /// Normalizes every text of `texts`, results are in the same order.
+    pub fn normalize_batch<S: AsRef<str> + Sync>(&self, texts: &[S]) -> Vec<String> {
+        map_ordered(texts, |text| self.normalize(text.as_ref()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Language, MosesPunctNormalizer, MosesTokenizer};
+
+    #[test]
+    fn test_batch_preserves_order() {
+        let texts: Vec<String> = (0..100)
+            .map(|i| format!("Line {} isn't «short»  , is it?", i))
+            .collect();
+
+        let tokenizer = MosesTokenizer::new(Language::En);
+        let batch = tokenizer.tokenize_batch(&texts, Some(false));
+        assert_eq!(batch.len(), texts.len());
+        for (text, tokens) in texts.iter().zip(&batch) {
+            assert_eq!(
+                tokens.as_str(),
+                tokenizer.tokenize(text.as_str(), Some(false)).as_str()
+            );
+        }
+
+        let normalizer = MosesPunctNormalizer::new(Language::En);
+        let batch = normalizer.normalize_batch(&texts);
+        for (text, normalized) in texts.iter().zip(&batch) {
+            assert_eq!(*normalized, normalizer.normalize(text.as_str()));
+        }
+
+        assert!(tokenizer.tokenize_batch::<&str>(&[], None).is_empty());
+    }
+}
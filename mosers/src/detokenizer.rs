@@ -0,0 +1,392 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+use regex::{Regex, RegexBuilder};
+
+use crate::{apply, char_class, unescape_xml, Language, PerlUniProps};
+
+/// Ranges of code points the Moses detokenizer treats as CJK.
+const CJK_RANGES: [(u32, u32); 8] = [
+    (4352, 4607),
+    (11904, 42191),
+    (43072, 43135),
+    (44032, 55215),
+    (63744, 64255),
+    (65072, 65103),
+    (65381, 65500),
+    (131072, 196607),
+];
+
+fn is_cjk(c: char) -> bool {
+    let c = c as u32;
+
+    CJK_RANGES
+        .iter()
+        .any(|(start, end)| *start <= c && c <= *end)
+}
+
+#[derive(Debug, Clone)]
+pub struct MosesDetokenizer {
+    lang: Language,
+}
+
+impl MosesDetokenizer {
+    pub fn new(lang: Language) -> MosesDetokenizer {
+        MosesDetokenizer { lang }
+    }
+
+    fn is_cs(&self) -> bool {
+        self.lang == Language::Cs || self.lang == Language::Cz
+    }
+
+    pub fn detokenize<I, S>(&self, tokens: I, unescape: Option<bool>) -> String
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        lazy_static! {
+            static ref INTRATOKEN_SPLITS: [(Regex, &'static str); 2] = [
+                (Regex::new(r" @-@ ").unwrap(), "-"),
+                (Regex::new(r" @/@ ").unwrap(), "/"),
+            ];
+            static ref RIGHT_SHIFT: Regex = Regex::new(&format!(
+                r"^[{}{}¿¡]+$",
+                PerlUniProps::CurrencySymbol.as_str(),
+                // Low quotation marks open quotes and are paired below
+                char_class(
+                    PerlUniProps::OpenPunctuation
+                        .chars()
+                        .filter(|c| !['‚', '„'].contains(c))
+                ),
+            ))
+            .unwrap();
+            static ref LEFT_SHIFT: Regex = Regex::new(&format!(
+                r"^[,.?!:;\\%{}]+$",
+                PerlUniProps::ClosePunctuation.as_str(),
+            ))
+            .unwrap();
+            static ref FR_SPACED_PUNCT: Regex = Regex::new(r"^[?!:;\\%]$").unwrap();
+            static ref EN_CONTRACTION: Regex =
+                Regex::new(&format!(r"^'[{}]", PerlUniProps::IsAlpha.as_str())).unwrap();
+            static ref ELISION: Regex =
+                Regex::new(&format!(r"[{}]'$", PerlUniProps::IsAlpha.as_str())).unwrap();
+            static ref ENDS_WITH_ALPHA: Regex =
+                Regex::new(&format!(r"[{}]$", PerlUniProps::IsAlpha.as_str())).unwrap();
+            static ref STARTS_WITH_ALPHA: Regex =
+                Regex::new(&format!(r"^[{}]", PerlUniProps::IsAlpha.as_str())).unwrap();
+            static ref NUMBER: Regex = Regex::new(r"^[0-9]+$").unwrap();
+            static ref DECIMAL_SEPARATOR: Regex = Regex::new(r"^[.,]$").unwrap();
+            static ref DASH: Regex = Regex::new(r"^[-–]$").unwrap();
+            static ref CS_DASHED_WORD: Regex = RegexBuilder::new(r"^li$|^mail.*")
+                .case_insensitive(true)
+                .build()
+                .unwrap();
+            static ref QUOTES: Regex = Regex::new("^['\"„“`]+$").unwrap();
+            static ref DOUBLE_QUOTES: Regex = Regex::new(r"^[„“”]+$").unwrap();
+            static ref FI_CASE_SUFFIX: Regex = Regex::new(
+                r"^(N|n|A|a|Ä|ä|ssa|Ssa|ssä|Ssä|sta|stä|Sta|Stä|hun|Hun|hyn|Hyn|han|Han|hän|Hän|hön|Hön|un|Un|yn|Yn|an|An|än|Än|ön|Ön|seen|Seen|lla|Lla|llä|Llä|lta|Lta|ltä|Ltä|lle|Lle|ksi|Ksi|kse|Kse|tta|Tta|ine|Ine)(ni|si|mme|nne|nsa)?(ko|kö|han|hän|pa|pä|kaan|kään|kin)?$"
+            )
+            .unwrap();
+            static ref SPACES: Regex = Regex::new(r" {2,}").unwrap();
+        }
+
+        let mut text = String::from(" ");
+        for token in tokens {
+            text.push_str(token.as_ref());
+            text.push(' ');
+        }
+        let text = apply(Cow::Owned(text), INTRATOKEN_SPLITS.iter());
+        let text = if unescape.unwrap_or(true) {
+            unescape_xml(&text).into_owned()
+        } else {
+            text
+        };
+
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        let mut quote_counts: HashMap<&str, usize> = HashMap::new();
+        let mut prepend_space = " ";
+        let mut detokenized = String::new();
+
+        let mut i = 0;
+        while i < tokens.len() {
+            let token = tokens[i];
+            let first = token.chars().next().unwrap();
+
+            if is_cjk(first) && self.lang != Language::Ko {
+                // Consecutive CJK words are joined without a space
+                if i > 0 && tokens[i - 1].chars().last().map(is_cjk).unwrap_or(false) {
+                    detokenized.push_str(token);
+                } else {
+                    detokenized.push_str(prepend_space);
+                    detokenized.push_str(token);
+                }
+                prepend_space = " ";
+            } else if RIGHT_SHIFT.is_match(token) {
+                detokenized.push_str(prepend_space);
+                detokenized.push_str(token);
+                prepend_space = "";
+            } else if LEFT_SHIFT.is_match(token) {
+                // French puts a space in front of these
+                if self.lang == Language::Fr && FR_SPACED_PUNCT.is_match(token) {
+                    detokenized.push(' ');
+                }
+                detokenized.push_str(token);
+                prepend_space = " ";
+            } else if self.lang == Language::En && i > 0 && EN_CONTRACTION.is_match(token) {
+                detokenized.push_str(token);
+                prepend_space = " ";
+            } else if self.is_cs()
+                && i > 1
+                && NUMBER.is_match(tokens[i - 2])
+                && DECIMAL_SEPARATOR.is_match(tokens[i - 1])
+                && NUMBER.is_match(token)
+            {
+                // Czech decimal numbers, e.g. "3 , 14"
+                detokenized.push_str(token);
+                prepend_space = " ";
+            } else if matches!(self.lang, Language::Fr | Language::It | Language::Ga)
+                && i + 1 < tokens.len()
+                && ELISION.is_match(token)
+                && STARTS_WITH_ALPHA.is_match(tokens[i + 1])
+            {
+                detokenized.push_str(prepend_space);
+                detokenized.push_str(token);
+                prepend_space = "";
+            } else if self.is_cs()
+                && i + 2 < tokens.len()
+                && ENDS_WITH_ALPHA.is_match(token)
+                && DASH.is_match(tokens[i + 1])
+                && CS_DASHED_WORD.is_match(tokens[i + 2])
+            {
+                // Czech "-li" and dashed words such as "e-mail"
+                detokenized.push_str(prepend_space);
+                detokenized.push_str(token);
+                detokenized.push_str(tokens[i + 1]);
+                prepend_space = "";
+                i += 1;
+            } else if QUOTES.is_match(token) {
+                let normalized_quote = if DOUBLE_QUOTES.is_match(token) {
+                    "\""
+                } else {
+                    token
+                };
+                let count = quote_counts.entry(normalized_quote).or_insert(0);
+                if self.is_cs() && token == "„" {
+                    *count = 0;
+                }
+                if self.is_cs() && token == "“" {
+                    *count = 1;
+                }
+
+                if count.is_multiple_of(2) {
+                    if self.lang == Language::En
+                        && token == "'"
+                        && i > 0
+                        && tokens[i - 1].ends_with('s')
+                    {
+                        // Possessives ending in "s", e.g. "The Jones' house"
+                        detokenized.push_str(token);
+                        prepend_space = " ";
+                    } else {
+                        detokenized.push_str(prepend_space);
+                        detokenized.push_str(token);
+                        prepend_space = "";
+                        *count += 1;
+                    }
+                } else {
+                    detokenized.push_str(token);
+                    prepend_space = " ";
+                    *count += 1;
+                }
+            } else if matches!(self.lang, Language::Fi)
+                && i > 0
+                && tokens[i - 1].ends_with(':')
+                && FI_CASE_SUFFIX.is_match(token)
+            {
+                // Case suffixes after a colon, e.g. "EU:n"
+                detokenized.push_str(&token.to_lowercase());
+                prepend_space = " ";
+            } else {
+                detokenized.push_str(prepend_space);
+                detokenized.push_str(token);
+                prepend_space = " ";
+            }
+
+            i += 1;
+        }
+
+        let detokenized = SPACES.replace_all(&detokenized, " ");
+
+        detokenized.trim().to_string()
+    }
+
+    /// Detokenizes the output of `MosesTokenizer::penn_tokenize`, turning
+    /// `-LRB-` and friends back into brackets, `` `` `` and `''` into `"` and
+    /// merging split contractions such as `gon na` and `do n't`.
+    pub fn penn_detokenize<I, S>(&self, tokens: I, unescape: Option<bool>) -> String
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        lazy_static! {
+            static ref PENN_BRACKETS: [(Regex, &'static str); 6] = [
+                (Regex::new(r"-LRB-").unwrap(), "("),
+                (Regex::new(r"-RRB-").unwrap(), ")"),
+                (Regex::new(r"-LSB-").unwrap(), "["),
+                (Regex::new(r"-RSB-").unwrap(), "]"),
+                (Regex::new(r"-LCB-").unwrap(), "{"),
+                (Regex::new(r"-RCB-").unwrap(), "}"),
+            ];
+            // The inverse of the contraction and quote rules of penn_tokenize
+            static ref PENN_CONTRACTIONS: [(Regex, &'static str); 14] = [
+                (Regex::new(r" ([Cc])an not ").unwrap(), " ${1}annot "),
+                (Regex::new(r" ([Dd])' ye ").unwrap(), " ${1}'ye "),
+                (Regex::new(r" ([Gg])im me ").unwrap(), " ${1}imme "),
+                (Regex::new(r" ([Gg])on na ").unwrap(), " ${1}onna "),
+                (Regex::new(r" ([Gg])ot ta ").unwrap(), " ${1}otta "),
+                (Regex::new(r" ([Ll])em me ").unwrap(), " ${1}emme "),
+                (Regex::new(r" ([Mm])ore 'n ").unwrap(), " ${1}ore'n "),
+                (Regex::new(r" '([Tt]) (is|was) ").unwrap(), " '${1}${2} "),
+                (Regex::new(r" ([Ww])an na ").unwrap(), " ${1}anna "),
+                (Regex::new(r" (n't|N'T) ").unwrap(), "${1} "),
+                (Regex::new(r" '(ll|re|ve|LL|RE|VE|[sSmMdD]) ").unwrap(), "'${1} "),
+                (Regex::new(r" `` ").unwrap(), " \" "),
+                (Regex::new(r" '' ").unwrap(), " \" "),
+                (Regex::new(r" ` ").unwrap(), " ' "),
+            ];
+        }
+
+        let mut text = String::from(" ");
+        for token in tokens {
+            text.push_str(token.as_ref());
+            text.push(' ');
+        }
+        let text = if unescape.unwrap_or(true) {
+            unescape_xml(&text).into_owned()
+        } else {
+            text
+        };
+        let text = apply(Cow::Owned(text), PENN_BRACKETS.iter());
+        let text = apply(Cow::Owned(text), PENN_CONTRACTIONS.iter());
+
+        self.detokenize(text.split_whitespace(), Some(false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Language, MosesDetokenizer, MosesTokenizer};
+
+    #[test]
+    fn test_roundtrip() {
+        let text = "This ain't funny. It's actually hillarious, yet double Ls. | [] < > & You're gonna shake it off? Don't?";
+        let tokenizer = MosesTokenizer::new(Language::En);
+        let detokenizer = MosesDetokenizer::new(Language::En);
+
+        let tkns = tokenizer.tokenize(text, Option::None);
+        assert_eq!(detokenizer.detokenize(tkns.tokens(), Option::None), text);
+
+        let text = "By the mid 1990s a version of the game became a Latvian television series (with a parliamentary setting, and played by Latvian celebrities).";
+        let tkns = tokenizer.tokenize(text, Option::None);
+        assert_eq!(detokenizer.detokenize(tkns.tokens(), Option::None), text);
+    }
+
+    #[test]
+    fn test_penn_roundtrip() {
+        let tokenizer = MosesTokenizer::new(Language::En);
+        let detokenizer = MosesDetokenizer::new(Language::En);
+
+        for text in [
+            "He said \"I'm gonna go (now)!\" and left.",
+            "It's John's; we'll see, don't you think? Gimme [that] {x} and/or more'n 5,300...",
+            "'Hello' she said, you gotta wanna d'ye cannot.",
+        ] {
+            let tkns = tokenizer.penn_tokenize(text, None);
+            assert_eq!(detokenizer.penn_detokenize(tkns.tokens(), None), text);
+        }
+
+        let tokenizer = MosesTokenizer::builder(Language::En)
+            .penn_bracket_symbols(false)
+            .build()
+            .unwrap();
+        let tkns = tokenizer.penn_tokenize("Go (now) [here]", Some(false));
+        assert_eq!(
+            detokenizer.penn_detokenize(tkns.tokens(), None),
+            "Go (now) [here]"
+        );
+    }
+
+    #[test]
+    fn test_intratoken_splits() {
+        let detokenizer = MosesDetokenizer::new(Language::En);
+
+        assert_eq!(
+            detokenizer.detokenize(["foo", "@-@", "bar", "and", "A", "@/@", "B"], Option::None),
+            "foo-bar and A/B"
+        );
+    }
+
+    #[test]
+    fn test_quotes_and_currency() {
+        let detokenizer = MosesDetokenizer::new(Language::En);
+
+        assert_eq!(
+            detokenizer.detokenize(
+                ["He", "paid", "$", "5", "for", "&quot;", "it", "&quot;", "."],
+                Option::None
+            ),
+            "He paid $5 for \"it\"."
+        );
+        assert_eq!(
+            detokenizer.detokenize(["The", "Jones", "&apos;", "house"], Option::None),
+            "The Jones' house"
+        );
+        assert_eq!(
+            detokenizer.detokenize(["&lt;", "b", "&gt;"], Option::Some(false)),
+            "&lt; b &gt;"
+        );
+    }
+
+    #[test]
+    fn test_language_specific() {
+        let detokenizer = MosesDetokenizer::new(Language::Fr);
+        assert_eq!(
+            detokenizer.detokenize(
+                ["Des", "gens", "admirent", "une", "œuvre", "d'", "art", "."],
+                None
+            ),
+            "Des gens admirent une œuvre d'art."
+        );
+        assert_eq!(
+            detokenizer.detokenize(["Pourquoi", "?"], None),
+            "Pourquoi ?"
+        );
+
+        let detokenizer = MosesDetokenizer::new(Language::Cs);
+        assert_eq!(
+            detokenizer.detokenize(["Pi", "je", "3", ",", "14", "."], None),
+            "Pi je 3,14."
+        );
+        assert_eq!(detokenizer.detokenize(["je", "-", "li"], None), "je-li");
+        assert_eq!(detokenizer.detokenize(["e", "-", "mail"], None), "e-mail");
+
+        let detokenizer = MosesDetokenizer::new(Language::Fi);
+        assert_eq!(
+            detokenizer.detokenize(["EU", ":", "n", "jäsen"], None),
+            "EU:n jäsen"
+        );
+
+        let detokenizer = MosesDetokenizer::new(Language::Zh);
+        assert_eq!(
+            detokenizer.detokenize(["我", "爱", "北京", "。"], None),
+            "我爱北京。"
+        );
+
+        let detokenizer = MosesDetokenizer::new(Language::Ko);
+        assert_eq!(
+            detokenizer.detokenize(["나는", "학생", "입니다"], None),
+            "나는 학생 입니다"
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use std::fmt;
+
+/// Errors returned by the fallible constructors of this crate.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The language code is not supported. `suggestions` holds supported
+    /// codes close to the given one.
+    UnknownLanguage {
+        code: String,
+        suggestions: Vec<&'static str>,
+    },
+    /// A regex failed to compile, e.g. a protected pattern with invalid
+    /// syntax or a character class over the regex size limit.
+    Regex(regex::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnknownLanguage { code, suggestions } => {
+                write!(f, "unknown language code '{}'", code)?;
+                if !suggestions.is_empty() {
+                    write!(f, ", did you mean '{}'?", suggestions.join("', '"))?;
+                }
+
+                Ok(())
+            }
+            Error::Regex(err) => write!(f, "failed to compile regex: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::UnknownLanguage { .. } => None,
+            Error::Regex(err) => Some(err),
+        }
+    }
+}
+
+impl From<regex::Error> for Error {
+    fn from(err: regex::Error) -> Error {
+        Error::Regex(err)
+    }
+}
@@ -0,0 +1,121 @@
+use std::borrow::Cow;
+
+/// Entities understood by `unescape_xml`, including the legacy ones produced
+/// by older Moses versions.
+const ENTITIES: [(&str, char); 11] = [
+    ("&amp;", '&'),
+    ("&#124;", '|'),
+    ("&lt;", '<'),
+    ("&gt;", '>'),
+    ("&quot;", '"'),
+    ("&apos;", '\''),
+    ("&#91;", '['),
+    ("&#93;", ']'),
+    ("&bar;", '|'),
+    ("&bra;", '['),
+    ("&ket;", ']'),
+];
+
+fn entity(byte: u8) -> Option<&'static str> {
+    match byte {
+        b'&' => Some("&amp;"),
+        b'|' => Some("&#124;"),
+        b'<' => Some("&lt;"),
+        b'>' => Some("&gt;"),
+        b'"' => Some("&quot;"),
+        b'\'' => Some("&apos;"),
+        b'[' => Some("&#91;"),
+        b']' => Some("&#93;"),
+        _ => None,
+    }
+}
+
+/// Replaces `& | < > " ' [ ]` with XML entities, as done by
+/// `MosesTokenizer::tokenize`. Only allocates when something is escaped.
+pub fn escape_xml(text: &str) -> Cow<'_, str> {
+    escape_with(text, entity)
+}
+
+/// `escape_xml` without `[` and `]`, which the Penn Treebank tokenization
+/// either rewrites to `-LSB-` and `-RSB-` or keeps literal.
+pub(crate) fn escape_xml_except_brackets(text: &str) -> Cow<'_, str> {
+    escape_with(text, |byte| match byte {
+        b'[' | b']' => None,
+        _ => entity(byte),
+    })
+}
+
+fn escape_with(text: &str, entity: impl Fn(u8) -> Option<&'static str>) -> Cow<'_, str> {
+    let mut escaped = String::new();
+    let mut last = 0;
+    // All escaped characters are ASCII, so byte indices are char boundaries
+    for (i, byte) in text.bytes().enumerate() {
+        if let Some(entity) = entity(byte) {
+            if escaped.is_empty() {
+                escaped.reserve(text.len() + 16);
+            }
+            escaped.push_str(&text[last..i]);
+            escaped.push_str(entity);
+            last = i + 1;
+        }
+    }
+
+    if escaped.is_empty() {
+        Cow::Borrowed(text)
+    } else {
+        escaped.push_str(&text[last..]);
+        Cow::Owned(escaped)
+    }
+}
+
+/// Port of Moses' `deescape-special-chars.perl`, the inverse of
+/// `escape_xml`. Only allocates when something is unescaped.
+pub fn unescape_xml(text: &str) -> Cow<'_, str> {
+    let mut unescaped = String::new();
+    let mut last = 0;
+    for (i, _) in text.match_indices('&') {
+        // Entities are never nested, `&amp;lt;` becomes `&lt;`
+        if i < last {
+            continue;
+        }
+        let rest = &text[i..];
+        if let Some((entity, c)) = ENTITIES.iter().find(|(entity, _)| rest.starts_with(entity)) {
+            if unescaped.is_empty() {
+                unescaped.reserve(text.len());
+            }
+            unescaped.push_str(&text[last..i]);
+            unescaped.push(*c);
+            last = i + entity.len();
+        }
+    }
+
+    if last == 0 {
+        Cow::Borrowed(text)
+    } else {
+        unescaped.push_str(&text[last..]);
+        Cow::Owned(unescaped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use crate::{escape_xml, unescape_xml};
+
+    #[test]
+    fn test_escape_roundtrip() {
+        let text = "a|b <c> [d] \"e\" & f's";
+        let escaped = escape_xml(text);
+        assert_eq!(
+            escaped,
+            "a&#124;b &lt;c&gt; &#91;d&#93; &quot;e&quot; &amp; f&apos;s"
+        );
+        assert_eq!(unescape_xml(&escaped), text);
+
+        assert!(matches!(escape_xml("plain ünïcode"), Cow::Borrowed(_)));
+        assert!(matches!(unescape_xml("a & b &unknown;"), Cow::Borrowed(_)));
+        assert_eq!(escape_xml("&"), "&amp;");
+        assert_eq!(unescape_xml("&amp;amp;&lt&gt;"), "&amp;&lt>");
+    }
+}
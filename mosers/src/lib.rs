@@ -1,11 +1,42 @@
 use std::borrow::Cow;
+use std::fmt;
+use std::ops::Index;
 use std::slice::Iter;
+use std::sync::{Arc, OnceLock};
 
 use lazy_static::lazy_static;
-use regex::Regex;
-use strum_macros::EnumString;
+use regex::{Captures, Regex, RegexBuilder};
+use strum::{EnumCount, IntoEnumIterator};
+use strum_macros::{EnumCount, EnumIter, EnumString};
 
-#[derive(Copy, Clone, PartialOrd, PartialEq, Debug, EnumString)]
+use crate::escape::escape_xml_except_brackets;
+
+mod perluniprops {
+    include!(concat!(env!("OUT_DIR"), "/perluniprops.rs"));
+}
+
+mod batch;
+mod detokenizer;
+mod error;
+mod escape;
+mod lowercaser;
+mod offsets;
+mod prefixes;
+mod splitter;
+mod stream;
+mod truecaser;
+
+pub use detokenizer::MosesDetokenizer;
+pub use error::Error;
+pub use escape::{escape_xml, unescape_xml};
+pub use lowercaser::MosesLowercaser;
+pub use offsets::TokenSpan;
+pub use prefixes::NonbreakingPrefixes;
+pub use splitter::MosesSentenceSplitter;
+pub use stream::{MosesIteratorExt, NormalizeWith, TokenizeWith};
+pub use truecaser::{MosesDetruecaser, MosesTruecaser};
+
+#[derive(Copy, Clone, PartialOrd, PartialEq, Debug, EnumString, EnumIter, EnumCount)]
 #[strum(ascii_case_insensitive)]
 pub enum Language {
     As,
@@ -13,6 +44,7 @@ pub enum Language {
     Ca,
     Cjk,
     Cs,
+    /// Alias of `Cs`, kept for backwards compatibility.
     Cz,
     De,
     El,
@@ -51,6 +83,172 @@ pub enum Language {
     Zh,
 }
 
+impl Language {
+    /// Parses a language code such as `en`, or an alias such as `cz` or
+    /// `ces`, suggesting close supported codes when it is unknown.
+    pub fn from_code(code: &str) -> Result<Language, Error> {
+        Language::parse_code(code, &code.to_ascii_lowercase())
+    }
+
+    /// Parses a BCP-47 or ISO 639 tag such as `en-US`, `pt_BR`, `zh-Hant`
+    /// or `ces`. Only the primary language subtag is used, and aliases like
+    /// `cz` map to the canonical language.
+    pub fn from_tag(tag: &str) -> Result<Language, Error> {
+        let primary = tag
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        Language::parse_code(tag, &primary)
+    }
+
+    /// Parses the lowercase `code_lower`, reporting `code` when unknown.
+    fn parse_code(code: &str, code_lower: &str) -> Result<Language, Error> {
+        let language = match code_lower {
+            "cz" | "ces" | "cze" => Language::Cs,
+            "asm" => Language::As,
+            "ben" => Language::Bn,
+            "cat" => Language::Ca,
+            "deu" | "ger" => Language::De,
+            "ell" | "gre" => Language::El,
+            "eng" => Language::En,
+            "spa" => Language::Es,
+            "est" => Language::Et,
+            "fin" => Language::Fi,
+            "fra" | "fre" => Language::Fr,
+            "gle" => Language::Ga,
+            "guj" => Language::Gu,
+            "hin" => Language::Hi,
+            "hun" => Language::Hu,
+            "isl" | "ice" => Language::Is,
+            "ita" => Language::It,
+            "jpn" => Language::Ja,
+            "kan" => Language::Kn,
+            "kor" => Language::Ko,
+            "lit" => Language::Lt,
+            "lav" | "lvs" => Language::Lv,
+            "mal" => Language::Ml,
+            "mar" => Language::Mr,
+            "nld" | "dut" => Language::Nl,
+            "ori" | "ory" => Language::Or,
+            "pan" => Language::Pa,
+            "pol" => Language::Pl,
+            "por" => Language::Pt,
+            "ron" | "rum" | "mo" => Language::Ro,
+            "rus" => Language::Ru,
+            "slk" | "slo" => Language::Sk,
+            "slv" => Language::Sl,
+            "swe" => Language::Sv,
+            "tam" => Language::Ta,
+            "tel" => Language::Te,
+            "zho" | "chi" | "cmn" => Language::Zh,
+            _ => {
+                return code_lower
+                    .parse()
+                    .map_err(|_| Language::unknown(code, code_lower))
+            }
+        };
+
+        Ok(language)
+    }
+
+    fn unknown(code: &str, code_lower: &str) -> Error {
+        let suggestions = Language::all()
+            .map(<&'static str>::from)
+            // Every code would start with an empty one
+            .filter(|_| !code_lower.is_empty())
+            .filter(|known| {
+                known.starts_with(code_lower)
+                    || code_lower.starts_with(known)
+                    || edit_distance(known, code_lower) <= 1
+            })
+            .collect();
+
+        Error::UnknownLanguage {
+            code: code.to_string(),
+            suggestions,
+        }
+    }
+
+    /// All supported languages, without aliases.
+    pub fn all() -> impl Iterator<Item = Language> {
+        Language::iter().filter(|lang| *lang != Language::Cz)
+    }
+
+    /// The language code, e.g. `en`.
+    pub fn code(&self) -> &'static str {
+        (*self).into()
+    }
+
+    /// The English name of the language, e.g. `English`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::As => "Assamese",
+            Language::Bn => "Bengali",
+            Language::Ca => "Catalan",
+            Language::Cjk => "Chinese, Japanese and Korean",
+            Language::Cs | Language::Cz => "Czech",
+            Language::De => "German",
+            Language::El => "Greek",
+            Language::En => "English",
+            Language::Es => "Spanish",
+            Language::Et => "Estonian",
+            Language::Fi => "Finnish",
+            Language::Fr => "French",
+            Language::Ga => "Irish",
+            Language::Gu => "Gujarati",
+            Language::Hi => "Hindi",
+            Language::Hu => "Hungarian",
+            Language::Is => "Icelandic",
+            Language::It => "Italian",
+            Language::Ja => "Japanese",
+            Language::Kn => "Kannada",
+            Language::Ko => "Korean",
+            Language::Lt => "Lithuanian",
+            Language::Lv => "Latvian",
+            Language::Ml => "Malayalam",
+            Language::Mni => "Manipuri",
+            Language::Mr => "Marathi",
+            Language::Nl => "Dutch",
+            Language::Or => "Odia",
+            Language::Pa => "Punjabi",
+            Language::Pl => "Polish",
+            Language::Pt => "Portuguese",
+            Language::Ro => "Romanian",
+            Language::Ru => "Russian",
+            Language::Sk => "Slovak",
+            Language::Sl => "Slovenian",
+            Language::Sv => "Swedish",
+            Language::Ta => "Tamil",
+            Language::Te => "Telugu",
+            Language::Yue => "Cantonese",
+            Language::Zh => "Chinese",
+        }
+    }
+
+    /// Whether a nonbreaking prefix list is bundled for the language.
+    pub fn has_nonbreaking_prefixes(&self) -> bool {
+        !Vec::<&'static str>::from(*self).is_empty()
+    }
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
 impl From<Language> for &'static str {
     fn from(language: Language) -> Self {
         match language {
@@ -105,7 +303,9 @@ impl From<Language> for Vec<&'static str> {
             Language::As => include_str!("../data/nonbreaking_prefixes/nonbreaking_prefix.as"),
             Language::Bn => include_str!("../data/nonbreaking_prefixes/nonbreaking_prefix.bn"),
             Language::Ca => include_str!("../data/nonbreaking_prefixes/nonbreaking_prefix.ca"),
-            Language::Cs => include_str!("../data/nonbreaking_prefixes/nonbreaking_prefix.cs"),
+            Language::Cs | Language::Cz => {
+                include_str!("../data/nonbreaking_prefixes/nonbreaking_prefix.cs")
+            }
             Language::De => include_str!("../data/nonbreaking_prefixes/nonbreaking_prefix.de"),
             Language::El => include_str!("../data/nonbreaking_prefixes/nonbreaking_prefix.el"),
             Language::En => include_str!("../data/nonbreaking_prefixes/nonbreaking_prefix.en"),
@@ -150,6 +350,7 @@ impl From<Language> for Vec<&'static str> {
     }
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug, EnumCount)]
 pub enum PerlUniProps {
     IsAlnum,
     Hiragana,
@@ -183,57 +384,122 @@ pub enum PerlUniProps {
 }
 
 impl PerlUniProps {
+    /// The characters with the property as the body of a regex character
+    /// class, e.g. `\x{30}-\x{39}`.
     fn as_str(&self) -> &'static str {
         match &self {
-            PerlUniProps::IsAlnum => include_str!("../data/perluniprops/IsAlnum.txt"),
-            PerlUniProps::Hiragana => include_str!("../data/perluniprops/Hiragana.txt"),
-            PerlUniProps::LineSeparator => include_str!("../data/perluniprops/Line_Separator.txt"),
-            PerlUniProps::IsN => include_str!("../data/perluniprops/IsN.txt"),
-            PerlUniProps::HangulSyllables => {
-                include_str!("../data/perluniprops/Hangul_Syllables.txt")
-            }
-            PerlUniProps::IsSc => include_str!("../data/perluniprops/IsSc.txt"),
-            PerlUniProps::IsLower => include_str!("../data/perluniprops/IsLower.txt"),
-            PerlUniProps::IsAlphaUnicharsAu => {
-                include_str!("../data/perluniprops/IsAlpha-unichars-au.txt")
-            }
-            PerlUniProps::Katakana => include_str!("../data/perluniprops/Katakana.txt"),
-            PerlUniProps::CurrencySymbol => {
-                include_str!("../data/perluniprops/Currency_Symbol.txt")
-            }
-            PerlUniProps::IsAlnumUnicharsAu => {
-                include_str!("../data/perluniprops/IsAlnum-unichars-au.txt")
-            }
-            PerlUniProps::IsUpper => include_str!("../data/perluniprops/IsUpper.txt"),
-            PerlUniProps::Han => include_str!("../data/perluniprops/Han.txt"),
-            PerlUniProps::Number => include_str!("../data/perluniprops/Number.txt"),
-            PerlUniProps::ClosePunctuation => {
-                include_str!("../data/perluniprops/Close_Punctuation.txt")
-            }
-            PerlUniProps::IsPi => include_str!("../data/perluniprops/IsPi.txt"),
-            PerlUniProps::IsPf => include_str!("../data/perluniprops/IsPf.txt"),
-            PerlUniProps::OpenPunctuation => {
-                include_str!("../data/perluniprops/Open_Punctuation.txt")
-            }
-            PerlUniProps::CJKSymbols => include_str!("../data/perluniprops/CJKSymbols.txt"),
-            PerlUniProps::Punctuation => include_str!("../data/perluniprops/Punctuation.txt"),
-            PerlUniProps::LowercaseLetter => {
-                include_str!("../data/perluniprops/Lowercase_Letter.txt")
-            }
-            PerlUniProps::Hangul => include_str!("../data/perluniprops/Hangul.txt"),
-            PerlUniProps::IsAlpha => include_str!("../data/perluniprops/IsAlpha.txt"),
-            PerlUniProps::IsSo => include_str!("../data/perluniprops/IsSo.txt"),
-            PerlUniProps::Separator => include_str!("../data/perluniprops/Separator.txt"),
-            PerlUniProps::TitlecaseLetter => {
-                include_str!("../data/perluniprops/Titlecase_Letter.txt")
-            }
-            PerlUniProps::UppercaseLetter => {
-                include_str!("../data/perluniprops/Uppercase_Letter.txt")
+            PerlUniProps::IsAlnum => perluniprops::ISALNUM_CLASS,
+            PerlUniProps::Hiragana => perluniprops::HIRAGANA_CLASS,
+            PerlUniProps::LineSeparator => perluniprops::LINE_SEPARATOR_CLASS,
+            PerlUniProps::IsN => perluniprops::ISN_CLASS,
+            PerlUniProps::HangulSyllables => perluniprops::HANGUL_SYLLABLES_CLASS,
+            PerlUniProps::IsSc => perluniprops::ISSC_CLASS,
+            PerlUniProps::IsLower => perluniprops::ISLOWER_CLASS,
+            PerlUniProps::IsAlphaUnicharsAu => perluniprops::ISALPHA_UNICHARS_AU_CLASS,
+            PerlUniProps::Katakana => perluniprops::KATAKANA_CLASS,
+            PerlUniProps::CurrencySymbol => perluniprops::CURRENCY_SYMBOL_CLASS,
+            PerlUniProps::IsAlnumUnicharsAu => perluniprops::ISALNUM_UNICHARS_AU_CLASS,
+            PerlUniProps::IsUpper => perluniprops::ISUPPER_CLASS,
+            PerlUniProps::Han => perluniprops::HAN_CLASS,
+            PerlUniProps::Number => perluniprops::NUMBER_CLASS,
+            PerlUniProps::ClosePunctuation => perluniprops::CLOSE_PUNCTUATION_CLASS,
+            PerlUniProps::IsPi => perluniprops::ISPI_CLASS,
+            PerlUniProps::IsPf => perluniprops::ISPF_CLASS,
+            PerlUniProps::OpenPunctuation => perluniprops::OPEN_PUNCTUATION_CLASS,
+            PerlUniProps::CJKSymbols => perluniprops::CJKSYMBOLS_CLASS,
+            PerlUniProps::Punctuation => perluniprops::PUNCTUATION_CLASS,
+            PerlUniProps::LowercaseLetter => perluniprops::LOWERCASE_LETTER_CLASS,
+            PerlUniProps::Hangul => perluniprops::HANGUL_CLASS,
+            PerlUniProps::IsAlpha => perluniprops::ISALPHA_CLASS,
+            PerlUniProps::IsSo => perluniprops::ISSO_CLASS,
+            PerlUniProps::Separator => perluniprops::SEPARATOR_CLASS,
+            PerlUniProps::TitlecaseLetter => perluniprops::TITLECASE_LETTER_CLASS,
+            PerlUniProps::UppercaseLetter => perluniprops::UPPERCASE_LETTER_CLASS,
+            PerlUniProps::CJK => perluniprops::CJK_CLASS,
+            PerlUniProps::Symbol => perluniprops::SYMBOL_CLASS,
+        }
+    }
+
+    /// Sorted, non-overlapping inclusive ranges of the characters with the
+    /// property.
+    fn ranges(&self) -> &'static [(char, char)] {
+        match &self {
+            PerlUniProps::IsAlnum => perluniprops::ISALNUM_RANGES,
+            PerlUniProps::Hiragana => perluniprops::HIRAGANA_RANGES,
+            PerlUniProps::LineSeparator => perluniprops::LINE_SEPARATOR_RANGES,
+            PerlUniProps::IsN => perluniprops::ISN_RANGES,
+            PerlUniProps::HangulSyllables => perluniprops::HANGUL_SYLLABLES_RANGES,
+            PerlUniProps::IsSc => perluniprops::ISSC_RANGES,
+            PerlUniProps::IsLower => perluniprops::ISLOWER_RANGES,
+            PerlUniProps::IsAlphaUnicharsAu => perluniprops::ISALPHA_UNICHARS_AU_RANGES,
+            PerlUniProps::Katakana => perluniprops::KATAKANA_RANGES,
+            PerlUniProps::CurrencySymbol => perluniprops::CURRENCY_SYMBOL_RANGES,
+            PerlUniProps::IsAlnumUnicharsAu => perluniprops::ISALNUM_UNICHARS_AU_RANGES,
+            PerlUniProps::IsUpper => perluniprops::ISUPPER_RANGES,
+            PerlUniProps::Han => perluniprops::HAN_RANGES,
+            PerlUniProps::Number => perluniprops::NUMBER_RANGES,
+            PerlUniProps::ClosePunctuation => perluniprops::CLOSE_PUNCTUATION_RANGES,
+            PerlUniProps::IsPi => perluniprops::ISPI_RANGES,
+            PerlUniProps::IsPf => perluniprops::ISPF_RANGES,
+            PerlUniProps::OpenPunctuation => perluniprops::OPEN_PUNCTUATION_RANGES,
+            PerlUniProps::CJKSymbols => perluniprops::CJKSYMBOLS_RANGES,
+            PerlUniProps::Punctuation => perluniprops::PUNCTUATION_RANGES,
+            PerlUniProps::LowercaseLetter => perluniprops::LOWERCASE_LETTER_RANGES,
+            PerlUniProps::Hangul => perluniprops::HANGUL_RANGES,
+            PerlUniProps::IsAlpha => perluniprops::ISALPHA_RANGES,
+            PerlUniProps::IsSo => perluniprops::ISSO_RANGES,
+            PerlUniProps::Separator => perluniprops::SEPARATOR_RANGES,
+            PerlUniProps::TitlecaseLetter => perluniprops::TITLECASE_LETTER_RANGES,
+            PerlUniProps::UppercaseLetter => perluniprops::UPPERCASE_LETTER_RANGES,
+            PerlUniProps::CJK => perluniprops::CJK_RANGES,
+            PerlUniProps::Symbol => perluniprops::SYMBOL_RANGES,
+        }
+    }
+
+    fn chars(&self) -> impl Iterator<Item = char> {
+        self.ranges().iter().flat_map(|&(start, end)| start..=end)
+    }
+
+    /// Whether `c` has the property.
+    pub fn contains(&self, c: char) -> bool {
+        self.ranges()
+            .binary_search_by(|(start, end)| {
+                if *end < c {
+                    std::cmp::Ordering::Less
+                } else if *start > c {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+}
+
+/// The body of a regex character class matching `chars`.
+fn char_class<I: IntoIterator<Item = char>>(chars: I) -> String {
+    let mut chars: Vec<char> = chars.into_iter().collect();
+    chars.sort_unstable();
+    chars.dedup();
+
+    let mut class = String::new();
+    let mut chars = chars.into_iter().peekable();
+    while let Some(start) = chars.next() {
+        let mut end = start;
+        while let Some(&next) = chars.peek() {
+            if next as u32 != end as u32 + 1 {
+                break;
             }
-            PerlUniProps::CJK => include_str!("../data/perluniprops/CJK.txt"),
-            PerlUniProps::Symbol => include_str!("../data/perluniprops/Symbol.txt"),
+            end = next;
+            chars.next();
+        }
+        class.push_str(&format!("\\x{{{:X}}}", start as u32));
+        if start != end {
+            class.push_str(&format!("-\\x{{{:X}}}", end as u32));
         }
     }
+
+    class
 }
 
 fn apply(text: Cow<str>, mut next: Iter<(Regex, &str)>) -> String {
@@ -254,6 +520,13 @@ pub struct MosesPunctNormalizer {
 }
 
 impl MosesPunctNormalizer {
+    /// Fallible counterpart of `new`, for symmetry with
+    /// `MosesTokenizer::try_new`. The normalizer rules are fixed patterns, so
+    /// this currently always succeeds.
+    pub fn try_new(lang: Language) -> Result<MosesPunctNormalizer, Error> {
+        Ok(MosesPunctNormalizer::new(lang))
+    }
+
     pub fn new(lang: Language) -> MosesPunctNormalizer {
         MosesPunctNormalizer::new_with_options(
             lang,
@@ -286,12 +559,12 @@ impl MosesPunctNormalizer {
     pub fn normalize<T: Into<String>>(&self, text: T) -> String {
         let text = text.into();
         let text = if *self.pre_replace_unicode_punct.as_ref().unwrap_or(&true) {
-            self.replace_unicode_punct(&*text)
+            self.replace_unicode_punct(&text)
         } else {
             text
         };
 
-        let text = self.extra_whitespace(&*text);
+        let text = self.extra_whitespace(&text);
 
         let text = if *self.penn.as_ref().unwrap_or(&true) {
             self.handle_penn_substitutions(text)
@@ -299,17 +572,17 @@ impl MosesPunctNormalizer {
             text
         };
 
-        let text = self.normalize_unicode(&*text);
-        let text = self.french_quotes(&*text);
-        let text = self.handle_pseudo_spaces(&*text);
+        let text = self.normalize_unicode(&text);
+        let text = self.french_quotes(&text);
+        let text = self.handle_pseudo_spaces(&text);
 
         let text = if *self.penn.as_ref().unwrap_or(&true) {
             lazy_static! {
                 static ref NORMALIZE_UNICODE_IF_NOT_PENN_0: Regex = Regex::new("`").unwrap();
                 static ref NORMALIZE_UNICODE_IF_NOT_PENN_1: Regex = Regex::new("''").unwrap();
             }
-            let text = NORMALIZE_UNICODE_IF_NOT_PENN_0.replace_all(&*text, "'");
-            let text = NORMALIZE_UNICODE_IF_NOT_PENN_1.replace_all(&*text, " \" ");
+            let text = NORMALIZE_UNICODE_IF_NOT_PENN_0.replace_all(&text, "'");
+            let text = NORMALIZE_UNICODE_IF_NOT_PENN_1.replace_all(&text, " \" ");
 
             text.into_owned()
         } else {
@@ -340,9 +613,11 @@ impl MosesPunctNormalizer {
         };
 
         if *self.post_remove_control_chars.as_ref().unwrap_or(&false) {
-            let remove_control_chars: Regex = Regex::new(r"\p{C}").unwrap();
+            lazy_static! {
+                static ref CONTROL_CHARS: Regex = Regex::new(r"\p{C}").unwrap();
+            }
 
-            remove_control_chars.replace_all(&*text, "").to_string()
+            CONTROL_CHARS.replace_all(&text, "").into_owned()
         } else {
             text
         }
@@ -393,9 +668,9 @@ impl MosesPunctNormalizer {
             static ref UNICODE_CIRCLE_SPACE_AFTER: Regex = Regex::new(r"。\s*").unwrap();
             static ref UNICODE_POINT_SPACE_AFTER: Regex = Regex::new(r"．\s*").unwrap();
         }
-        let text = UNICODE_POINT.replace(&*text, "...");
-        let text = UNICODE_CIRCLE_SPACE_AFTER.replace_all(&*text, ". ");
-        let text = UNICODE_POINT_SPACE_AFTER.replace_all(&*text, ". ");
+        let text = UNICODE_POINT.replace(&text, "...");
+        let text = UNICODE_CIRCLE_SPACE_AFTER.replace_all(&text, ". ");
+        let text = UNICODE_POINT_SPACE_AFTER.replace_all(&text, ". ");
 
         text.to_string()
     }
@@ -407,10 +682,10 @@ impl MosesPunctNormalizer {
                 (Regex::new(r"\(").unwrap(), r" ("),
                 (Regex::new(r"\)").unwrap(), r") "),
                 (Regex::new(r" +").unwrap(), r" "),
-                (Regex::new(r"\) ([.!:?;,])").unwrap(), r"\)\g$1"),
+                (Regex::new(r"\) ([.!:?;,])").unwrap(), r")${1}"),
                 (Regex::new(r"\( ").unwrap(), r"("),
                 (Regex::new(r" \)").unwrap(), r")"),
-                (Regex::new(r"(\d) %").unwrap(), r"\g$1%"),
+                (Regex::new(r"(\d) %").unwrap(), r"${1}%"),
                 (Regex::new(r" :").unwrap(), r":"),
                 (Regex::new(r" ;").unwrap(), r";"),
             ];
@@ -429,8 +704,8 @@ impl MosesPunctNormalizer {
                 (Regex::new(r"—").unwrap(), r" - "),
                 (Regex::new(r" +").unwrap(), r" "),
                 (Regex::new(r"´").unwrap(), r"'"),
-                (Regex::new(r"([a-zA-Z])‘([a-zA-Z])").unwrap(), r"\g$1'\g$2"),
-                (Regex::new(r"([a-zA-Z])’([a-zA-Z])").unwrap(), r"\g$1'\g$2"),
+                (Regex::new(r"([a-zA-Z])‘([a-zA-Z])").unwrap(), r"${1}'${2}"),
+                (Regex::new(r"([a-zA-Z])’([a-zA-Z])").unwrap(), r"${1}'${2}"),
                 (Regex::new(r"‘").unwrap(), r"'"),
                 (Regex::new(r"‚").unwrap(), r"'"),
                 (Regex::new(r"’").unwrap(), r"'"),
@@ -466,8 +741,8 @@ impl MosesPunctNormalizer {
                 (Regex::new(r"\u00A0:").unwrap(), r":"),
                 (Regex::new(r"\u00A0ºC").unwrap(), r" ºC"),
                 (Regex::new(r"\u00A0cm").unwrap(), r" cm"),
-                (Regex::new(r"\u00A0\\?").unwrap(), r"?"),
-                (Regex::new(r"\u00A0\\!").unwrap(), r"!"),
+                (Regex::new(r"\u00A0\?").unwrap(), r"?"),
+                (Regex::new(r"\u00A0!").unwrap(), r"!"),
                 (Regex::new(r"\u00A0;").unwrap(), r";"),
                 (Regex::new(r",\u00A0").unwrap(), r", "),
                 (Regex::new(r" +").unwrap(), r" "),
@@ -478,197 +753,486 @@ impl MosesPunctNormalizer {
     }
 
     fn handle_penn_substitutions(&self, text: String) -> String {
-        let text = Regex::new(r"`").unwrap().replace_all(&*text, "'");
-        let text = Regex::new(r"''").unwrap().replace_all(&*text, " \" ");
+        lazy_static! {
+            static ref PENN_SUBSTITUTIONS: [(Regex, &'static str); 2] = [
+                (Regex::new(r"`").unwrap(), "'"),
+                (Regex::new(r"''").unwrap(), " \" "),
+            ];
+        }
 
-        text.to_string()
+        apply(Cow::Owned(text), PENN_SUBSTITUTIONS.iter())
     }
 
     fn handle_en_quotation_followed_by_comma(&self, text: String) -> String {
-        let text = Regex::new("\"([,.]+)")
-            .unwrap()
-            .replace_all(&*text, "\\g$1\"");
+        lazy_static! {
+            static ref EN_QUOTATION_FOLLOWED_BY_COMMA: [(Regex, &'static str); 1] =
+                [(Regex::new("\"([,.]+)").unwrap(), "${1}\"")];
+        }
 
-        text.to_string()
+        apply(Cow::Owned(text), EN_QUOTATION_FOLLOWED_BY_COMMA.iter())
     }
 
     fn handle_de_es_fr_quotation_followed_by_comma(&self, text: String) -> String {
-        let text = Regex::new(",\"").unwrap().replace_all(&*text, "\",");
-        let text = Regex::new("(\\.+)\"(\\s*[^<])")
-            .unwrap()
-            .replace_all(&*text, "\"\\g$1\\g$2");
+        lazy_static! {
+            static ref DE_ES_FR_QUOTATION_FOLLOWED_BY_COMMA: [(Regex, &'static str); 2] = [
+                (Regex::new(",\"").unwrap(), "\","),
+                (Regex::new("(\\.+)\"(\\s*[^<])").unwrap(), "\"${1}${2}"),
+            ];
+        }
 
-        text.to_string()
+        apply(
+            Cow::Owned(text),
+            DE_ES_FR_QUOTATION_FOLLOWED_BY_COMMA.iter(),
+        )
     }
 
     fn handle_numbers_comma(&self, text: String) -> String {
-        let text = Regex::new("(\\d)\\u00A0(\\d)")
-            .unwrap()
-            .replace_all(&*text, "\\g$1,\\g$2");
+        lazy_static! {
+            static ref NUMBERS_COMMA: [(Regex, &'static str); 1] =
+                [(Regex::new("(\\d)\\u00A0(\\d)").unwrap(), "${1},${2}")];
+        }
 
-        text.to_string()
+        apply(Cow::Owned(text), NUMBERS_COMMA.iter())
     }
 
     fn handle_numbers_point(&self, text: String) -> String {
-        let text = Regex::new("(\\d)\\u00A0(\\d)")
-            .unwrap()
-            .replace_all(&*text, "\\g$1.\\g$2");
+        lazy_static! {
+            static ref NUMBERS_POINT: [(Regex, &'static str); 1] =
+                [(Regex::new("(\\d)\\u00A0(\\d)").unwrap(), "${1}.${2}")];
+        }
 
-        text.to_string()
+        apply(Cow::Owned(text), NUMBERS_POINT.iter())
     }
 }
 
 const VIRAMAS: &str = "\\u094D\\u09CD\\u0A4D\\u0ACD\\u0B4D\\u0BCD\\u0C4D\\u0CCD\\u0D3B\\u0D3C\\u0D4D\\u0EBA\\u1039\\u1714\\u1BAB\\uA8C4\\uA8F3\\uA8F4\\uA953\\uAAF6\\U00010A3F\\U00011046\\U000110B9\\U00011133\\U000111C0\\U00011235\\U000112EA\\U0001134D\\U00011442\\U000114C2\\U000115BF\\U0001163F\\U000116B6\\U00011839\\U000119E0\\U00011A34\\U00011C3F\\U00011D45\\U00011D97\\u0DCA";
 const NUKTAS: &str = "\\u093C\\u09BC\\u0A3C\\u0ABC\\u0AFD\\u0AFE\\u0AFF\\u0B3C\\u0CBC\\u1C37\\U000110BA\\U00011173\\U000111CA\\U00011236\\U000112E9\\U0001133C\\U00011446\\U000114C3\\U000115C0\\U000116B7\\U0001183A\\U00011D42\\U0001E94A";
 
-#[derive(Debug, Clone)]
-pub struct MosesTokenizer {
+pub const BASIC_PROTECTED_PATTERNS: [&str; 5] = [
+    r"</?\S+/?>",
+    r#"<\S+( [a-zA-Z0-9]+="?[^"]")+ ?/?>"#,
+    r"<\S+( [a-zA-Z0-9]+='?[^']')+ ?/?>",
+    r"[\w\-_.]+@([\w\-_]+\.)+[a-zA-Z]{2,}",
+    r"(http[s]?|ftp)://[^:/\s]+(/\w+)*/[\w\-.]+",
+];
+
+// Control characters are stripped before tokenizing, so these never collide
+// with the input
+const PROTECTED_PLACEHOLDER: char = '\u{2}';
+const MULTIDOT_PLACEHOLDER: char = '\u{1}';
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MosesTokenizerOptions {
+    /// Split hyphens between alphanumerics into ` @-@ `.
+    pub aggressive_dash_splits: bool,
+    /// Split slashes between alphanumerics into ` @/@ `.
+    pub intratoken_slashes: bool,
+    /// Escape XML special characters, unless overridden per call.
+    pub escape: bool,
+    /// Run `penn_tokenize` instead of the default Moses rules.
+    pub penn: bool,
+    /// Rewrite brackets to `-LRB-`, `-RRB-`, `-LSB-`, `-RSB-`, `-LCB-` and
+    /// `-RCB-` in `penn_tokenize`, instead of keeping them literal.
+    pub penn_bracket_symbols: bool,
+    /// Put every Han, kana or Hangul character in its own token for `Zh`,
+    /// `Ja`, `Ko` and `Cjk`, instead of keeping runs of them together.
+    pub split_cjk_chars: bool,
+}
+
+impl Default for MosesTokenizerOptions {
+    fn default() -> Self {
+        MosesTokenizerOptions {
+            aggressive_dash_splits: true,
+            intratoken_slashes: false,
+            escape: true,
+            penn: false,
+            penn_bracket_symbols: true,
+            split_cjk_chars: false,
+        }
+    }
+}
+
+pub struct MosesTokenizerBuilder {
     lang: Language,
-    nonbreaking_prefixes: Vec<&'static str>,
-    numeric_only_prefixes: Vec<&'static str>,
-    pad_not_isalnum: (Regex, &'static str),
-    aggressive_hyphen_split: (fancy_regex::Regex, &'static str),
-    intratoken_slashes: (fancy_regex::Regex, &'static str),
+    options: MosesTokenizerOptions,
+    protected_patterns: Vec<String>,
+    nonbreaking_prefixes: Option<NonbreakingPrefixes>,
 }
 
-impl MosesTokenizer {
-    pub fn new(lang: Language) -> MosesTokenizer {
-        let nonbreaking_prefixes: Vec<&'static str> = lang.into();
-        let numeric_only_prefixes: Vec<&'static str> = nonbreaking_prefixes
-            .iter()
-            .filter(|prefix| MosesTokenizer::has_numeric_only(**prefix))
-            .map(|prefix| {
-                let splits: Vec<&str> = prefix.rsplit(' ').collect();
+impl MosesTokenizerBuilder {
+    pub fn new(lang: Language) -> MosesTokenizerBuilder {
+        MosesTokenizerBuilder {
+            lang,
+            options: MosesTokenizerOptions::default(),
+            protected_patterns: Vec::new(),
+            nonbreaking_prefixes: None,
+        }
+    }
 
-                *splits.last().unwrap()
-            })
+    pub fn options(mut self, options: MosesTokenizerOptions) -> MosesTokenizerBuilder {
+        self.options = options;
+        self
+    }
+
+    pub fn aggressive_dash_splits(mut self, aggressive_dash_splits: bool) -> MosesTokenizerBuilder {
+        self.options.aggressive_dash_splits = aggressive_dash_splits;
+        self
+    }
+
+    pub fn intratoken_slashes(mut self, intratoken_slashes: bool) -> MosesTokenizerBuilder {
+        self.options.intratoken_slashes = intratoken_slashes;
+        self
+    }
+
+    pub fn escape(mut self, escape: bool) -> MosesTokenizerBuilder {
+        self.options.escape = escape;
+        self
+    }
+
+    pub fn penn(mut self, penn: bool) -> MosesTokenizerBuilder {
+        self.options.penn = penn;
+        self
+    }
+
+    pub fn penn_bracket_symbols(mut self, penn_bracket_symbols: bool) -> MosesTokenizerBuilder {
+        self.options.penn_bracket_symbols = penn_bracket_symbols;
+        self
+    }
+
+    pub fn split_cjk_chars(mut self, split_cjk_chars: bool) -> MosesTokenizerBuilder {
+        self.options.split_cjk_chars = split_cjk_chars;
+        self
+    }
+
+    pub fn protected_patterns<I, S>(mut self, patterns: I) -> MosesTokenizerBuilder
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.protected_patterns = patterns
+            .into_iter()
+            .map(|pattern| pattern.as_ref().to_string())
             .collect();
+        self
+    }
 
-        let cjk_chars = String::new();
-        match lang {
-            Language::Zh | Language::Ja | Language::Ko | Language::Cjk => {
-                let mut cjk_chars = String::new();
+    /// Replaces the bundled nonbreaking prefixes of the language.
+    pub fn nonbreaking_prefixes(mut self, prefixes: NonbreakingPrefixes) -> MosesTokenizerBuilder {
+        self.nonbreaking_prefixes = Some(prefixes);
+        self
+    }
 
-                match lang {
-                    Language::Ko | Language::Cjk => {
-                        cjk_chars.push_str(PerlUniProps::Hangul.as_str());
-                    }
-                    _ => {}
-                };
-                match lang {
-                    Language::Zh | Language::Cjk => {
-                        cjk_chars.push_str(PerlUniProps::Han.as_str());
-                    }
-                    _ => {}
-                };
-                match lang {
-                    Language::Ja | Language::Cjk => {
-                        cjk_chars.push_str(PerlUniProps::Hiragana.as_str());
-                        cjk_chars.push_str(PerlUniProps::Katakana.as_str());
-                        cjk_chars.push_str(PerlUniProps::Han.as_str());
-                    }
-                    _ => {}
-                };
-            }
-            _ => {}
+    pub fn build(self) -> Result<MosesTokenizer, Error> {
+        let tokenizer = MosesTokenizer::from_options(self.lang, self.options)?
+            .with_protected_patterns(self.protected_patterns)?;
+
+        Ok(match self.nonbreaking_prefixes {
+            Some(prefixes) => tokenizer.with_nonbreaking_prefixes(prefixes),
+            None => tokenizer,
+        })
+    }
+}
+
+/// The regexes built from the character tables of a language. They are
+/// expensive to compile, so they are cached and shared by all tokenizers with
+/// the same language and options.
+#[derive(Debug)]
+struct TokenizerRegexes {
+    pad_not_isalnum: (Regex, &'static str),
+    /// Matches an alphanumeric character at the start of the text, used to
+    /// split hyphens and slashes between alphanumerics.
+    leading_alnum: Regex,
+    cjk_char_split: Option<(Regex, &'static str)>,
+}
+
+impl TokenizerRegexes {
+    fn cached(
+        lang: Language,
+        options: &MosesTokenizerOptions,
+    ) -> Result<Arc<TokenizerRegexes>, Error> {
+        // Only these two options change the regexes
+        static CACHE: [OnceLock<Arc<TokenizerRegexes>>; 4 * Language::COUNT] =
+            [const { OnceLock::new() }; 4 * Language::COUNT];
+
+        let index = 4 * lang as usize
+            + 2 * options.intratoken_slashes as usize
+            + options.split_cjk_chars as usize;
+        if let Some(regexes) = CACHE[index].get() {
+            return Ok(regexes.clone());
         }
+        // Threads racing here compile the same regexes, the first one wins
+        let regexes = Arc::new(TokenizerRegexes::new(lang, options)?);
+
+        Ok(CACHE[index].get_or_init(|| regexes).clone())
+    }
+
+    fn new(lang: Language, options: &MosesTokenizerOptions) -> Result<TokenizerRegexes, Error> {
+        let scripts: &[PerlUniProps] = match lang {
+            Language::Zh => &[PerlUniProps::Han],
+            Language::Ja => &[
+                PerlUniProps::Hiragana,
+                PerlUniProps::Katakana,
+                PerlUniProps::Han,
+            ],
+            Language::Ko => &[PerlUniProps::Hangul],
+            Language::Cjk => &[
+                PerlUniProps::Hangul,
+                PerlUniProps::Han,
+                PerlUniProps::Hiragana,
+                PerlUniProps::Katakana,
+            ],
+            _ => &[],
+        };
+        // The script tables also list the punctuation shared by the scripts,
+        // such as "。" and "「", which is still padded like elsewhere
+        let cjk_chars = char_class(
+            scripts
+                .iter()
+                .flat_map(|script| script.chars())
+                .filter(|&c| {
+                    !c.is_whitespace()
+                        && !PerlUniProps::Punctuation.contains(c)
+                        && !PerlUniProps::Symbol.contains(c)
+                }),
+        );
 
-        let mut is_alpha = String::new();
-        is_alpha.push_str(PerlUniProps::IsAlpha.as_str());
-        is_alpha.push_str(VIRAMAS);
-        is_alpha.push_str(NUKTAS);
-        is_alpha.push_str(&*cjk_chars);
         let mut is_alnum = String::new();
         is_alnum.push_str(PerlUniProps::IsAlnum.as_str());
         is_alnum.push_str(VIRAMAS);
         is_alnum.push_str(NUKTAS);
-        is_alnum.push_str(&*cjk_chars);
+        is_alnum.push_str(&cjk_chars);
 
+        // In Finnish and Swedish the colon can be used inside words as an
+        // apostrophe-like character (USA:n, 20:een, EU:ssa, USA:s, S:t)
+        let colon = match lang {
+            Language::Fi | Language::Sv => ":",
+            _ => "",
+        };
+        // Intratoken slashes are split after padding, like hyphens
+        let slash = if options.intratoken_slashes { "/" } else { "" };
+        // Protected pattern placeholders are kept in one piece
         let pad_not_isalnum = (
-            Regex::new(&*format!("([^{}\\s\\.'`,-])", &is_alnum)).unwrap(),
+            Regex::new(&format!(
+                "([^{}\\s\\.{}{}\\x02'`,-])",
+                &is_alnum, colon, slash
+            ))?,
             " $1 ",
         );
-        let aggressive_hyphen_split = (
-            fancy_regex::Regex::new(&*format!("([{}])\\-(?=[{}])", &is_alnum, &is_alnum)).unwrap(),
-            r"$1 @-@ ",
-        );
-        let intratoken_slashes = (
-            fancy_regex::Regex::new(&*format!("([{}])/([{}])", &is_alnum, &is_alnum)).unwrap(),
-            r"$1 \@/\@ $2",
-        );
+        let leading_alnum = Regex::new(&format!("^[{}]", &is_alnum))?;
+        let cjk_char_split = if options.split_cjk_chars && !cjk_chars.is_empty() {
+            Some((Regex::new(&format!("([{}])", &cjk_chars))?, " $1 "))
+        } else {
+            None
+        };
 
-        MosesTokenizer {
-            lang,
-            nonbreaking_prefixes,
-            numeric_only_prefixes,
+        Ok(TokenizerRegexes {
             pad_not_isalnum,
-            aggressive_hyphen_split,
-            intratoken_slashes,
-        }
+            leading_alnum,
+            cjk_char_split,
+        })
     }
+}
 
-    fn replace_multidots(&self, text: String) -> String {
-        lazy_static! {
-            static ref DOTMULTI: Regex = Regex::new(r"\.(\.+)").unwrap();
-            static ref MORE: Regex = Regex::new(r"DOTMULTI\.").unwrap();
-            static ref R1: Regex = Regex::new(r"DOTMULTI\.([^\.])").unwrap();
-            static ref R2: Regex = Regex::new(r"DOTMULTI\.").unwrap();
+#[derive(Debug, Clone)]
+pub struct MosesTokenizer {
+    lang: Language,
+    options: MosesTokenizerOptions,
+    nonbreaking_prefixes: NonbreakingPrefixes,
+    regexes: Arc<TokenizerRegexes>,
+    protected_patterns: Vec<Regex>,
+}
+
+impl MosesTokenizer {
+    /// Creates a tokenizer with the default options.
+    ///
+    /// Panics if one of its regexes fails to compile, see `try_new`.
+    pub fn new(lang: Language) -> MosesTokenizer {
+        MosesTokenizer::try_new(lang).unwrap()
+    }
+
+    /// Creates a tokenizer with the default options, returning an error
+    /// instead of panicking if one of its regexes fails to compile.
+    pub fn try_new(lang: Language) -> Result<MosesTokenizer, Error> {
+        MosesTokenizer::from_options(lang, MosesTokenizerOptions::default())
+    }
+
+    fn from_options(
+        lang: Language,
+        options: MosesTokenizerOptions,
+    ) -> Result<MosesTokenizer, Error> {
+        Ok(MosesTokenizer {
+            lang,
+            options,
+            nonbreaking_prefixes: NonbreakingPrefixes::for_language(lang),
+            regexes: TokenizerRegexes::cached(lang, &options)?,
+            protected_patterns: Vec::new(),
+        })
+    }
+
+    /// A tokenizer with the default options, created on first use and shared
+    /// by the whole process afterwards.
+    ///
+    /// Panics if one of its regexes fails to compile, see `try_new`.
+    pub fn shared(lang: Language) -> Arc<MosesTokenizer> {
+        static SHARED: [OnceLock<Arc<MosesTokenizer>>; Language::COUNT] =
+            [const { OnceLock::new() }; Language::COUNT];
+
+        SHARED[lang as usize]
+            .get_or_init(|| Arc::new(MosesTokenizer::new(lang)))
+            .clone()
+    }
+
+    pub fn builder(lang: Language) -> MosesTokenizerBuilder {
+        MosesTokenizerBuilder::new(lang)
+    }
+
+    pub fn options(&self) -> &MosesTokenizerOptions {
+        &self.options
+    }
+
+    /// Registers regexes whose matches are kept verbatim by `tokenize`.
+    /// Patterns are matched case-insensitively, like in sacremoses.
+    pub fn with_protected_patterns<I, S>(mut self, patterns: I) -> Result<MosesTokenizer, Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.protected_patterns = patterns
+            .into_iter()
+            .map(|pattern| {
+                RegexBuilder::new(pattern.as_ref())
+                    .case_insensitive(true)
+                    .build()
+            })
+            .collect::<Result<Vec<Regex>, regex::Error>>()?;
+
+        Ok(self)
+    }
+
+    /// Replaces the nonbreaking prefixes, e.g. with the bundled ones extended
+    /// by domain specific abbreviations.
+    pub fn with_nonbreaking_prefixes(mut self, prefixes: NonbreakingPrefixes) -> MosesTokenizer {
+        self.nonbreaking_prefixes = prefixes;
+        self
+    }
+
+    pub fn nonbreaking_prefixes(&self) -> &NonbreakingPrefixes {
+        &self.nonbreaking_prefixes
+    }
+
+    fn protected_placeholder(index: usize) -> String {
+        format!("{0}{1}{0}", PROTECTED_PLACEHOLDER, index)
+    }
+
+    fn protect_patterns(&self, text: &str) -> (String, Vec<String>) {
+        let protected_tokens: Vec<String> = self
+            .protected_patterns
+            .iter()
+            .flat_map(|pattern| pattern.find_iter(text))
+            .map(|token| token.as_str().to_string())
+            .collect();
+
+        let mut text = text.to_string();
+        for (i, token) in protected_tokens.iter().enumerate() {
+            text = text.replace(token.as_str(), &MosesTokenizer::protected_placeholder(i));
         }
 
-        let mut text = DOTMULTI.replace_all(&*text, r" DOTMULTI$1").into_owned();
-        while MORE.find(&*text).is_some() {
-            let ltext = R1.replace_all(&*text, "DOTDOTMULTI $1");
-            text = R2.replace_all(ltext.as_ref(), "DOTDOTMULTI").into_owned();
+        (text, protected_tokens)
+    }
+
+    fn restore_protected_patterns(&self, text: &str, protected_tokens: &[String]) -> String {
+        let mut text = text.to_string();
+        for (i, token) in protected_tokens.iter().enumerate() {
+            text = text.replace(&MosesTokenizer::protected_placeholder(i), token);
         }
 
         text
     }
 
-    fn restore_multidots(&self, text: String) -> String {
+    /// Replaces each `separator` between two alphanumeric characters with
+    /// `replacement`. Like `([alnum])-(?=[alnum])` with lookahead, so `a-b-c`
+    /// splits twice, but in linear time.
+    fn split_between_alnums(&self, text: &str, separator: char, replacement: &str) -> String {
+        let leading_alnum = &self.regexes.leading_alnum;
+        let mut split = String::with_capacity(text.len());
+        let mut last = 0;
+        for (i, _) in text.match_indices(separator) {
+            let previous = match text[..i].chars().next_back() {
+                Some(previous) => &text[i - previous.len_utf8()..i],
+                None => continue,
+            };
+            let next = &text[i + separator.len_utf8()..];
+            if leading_alnum.is_match(previous) && leading_alnum.is_match(next) {
+                split.push_str(&text[last..i]);
+                split.push_str(replacement);
+                last = i + separator.len_utf8();
+            }
+        }
+        split.push_str(&text[last..]);
+
+        split
+    }
+
+    /// Replaces every run of two or more dots with `MULTIDOT_PLACEHOLDER` and
+    /// returns the length of each run, in order.
+    fn replace_multidots(&self, text: &str) -> (String, Vec<usize>) {
         lazy_static! {
-            static ref DOTDOTMULTI: Regex = Regex::new("DOTDOTMULTI").unwrap();
-            static ref DOTMULTI: Regex = Regex::new("DOTMULTI").unwrap();
+            static ref MULTIDOTS: Regex = Regex::new(r"\.{2,}").unwrap();
         }
 
-        let mut text = text;
-        while DOTDOTMULTI.find(&*text).is_some() {
-            text = DOTDOTMULTI.replace_all(&*text, "DOTMULTI.").into_owned();
+        let mut runs = Vec::new();
+        let mut replaced = String::with_capacity(text.len());
+        let mut last = 0;
+        for dots in MULTIDOTS.find_iter(text) {
+            replaced.push_str(&text[last..dots.start()]);
+            replaced.push(' ');
+            replaced.push(MULTIDOT_PLACEHOLDER);
+            if dots.end() < text.len() {
+                replaced.push(' ');
+            }
+            runs.push(dots.len());
+            last = dots.end();
         }
+        replaced.push_str(&text[last..]);
 
-        DOTMULTI.replace_all(&*text, ".").into_owned()
+        (replaced, runs)
     }
 
-    fn is_lower(text: &str) -> bool {
-        let lower_chars: &'static str = PerlUniProps::IsLower.as_str(); // TODO lazy const set
+    fn restore_multidots(&self, text: &str, runs: &[usize]) -> String {
+        if runs.is_empty() {
+            return text.to_string();
+        }
 
-        text.chars()
-            .all(|tc| lower_chars.chars().any(|lc| lc == tc))
-    }
+        let mut runs = runs.iter();
+        let mut restored = String::with_capacity(text.len() + runs.len());
+        for (i, part) in text.split(MULTIDOT_PLACEHOLDER).enumerate() {
+            if i > 0 {
+                restored.extend(std::iter::repeat_n('.', *runs.next().unwrap_or(&0)));
+            }
+            restored.push_str(part);
+        }
 
-    fn is_any_alpha(text: &str) -> bool {
-        let alpha_chars: &'static str = PerlUniProps::IsAlpha.as_str(); // TODO lazy const set
+        restored
+    }
 
+    fn starts_lowercase(text: &str) -> bool {
         text.chars()
-            .any(|tc| alpha_chars.chars().any(|lc| lc == tc))
+            .next()
+            .is_some_and(|c| PerlUniProps::IsLower.contains(c))
     }
 
-    fn has_numeric_only(text: &str) -> bool {
-        lazy_static! {
-            static ref NUMERIC_ONLY: Regex = Regex::new(r"[\s]+(#NUMERIC_ONLY#)").unwrap();
-        }
-
-        NUMERIC_ONLY.find(text).is_some()
+    fn is_any_alpha(text: &str) -> bool {
+        text.chars().any(|c| PerlUniProps::IsAlpha.contains(c))
     }
 
     fn handles_nonbreaking_prefixes(&self, text: &str) -> String {
         lazy_static! {
-            static ref SPLIT_WS: Regex = Regex::new(r"\s+").unwrap();
             static ref RE_END_WITH_PERIOD: Regex = Regex::new(r"^(\S+)\.$").unwrap();
             static ref RE_NUM: Regex = Regex::new(r"^[0-9]+").unwrap();
         };
 
         let mut collector = String::new();
-        let tokens: Vec<&str> = SPLIT_WS.split(text).collect();
+        let tokens: Vec<&str> = text.split_whitespace().collect();
         tokens.iter().enumerate().for_each(|(i, token)| {
             if i != 0 {
                 collector.push(' ');
@@ -676,56 +1240,39 @@ impl MosesTokenizer {
             let token_ends_with_period = RE_END_WITH_PERIOD.captures(token);
             if let Some(token_ends_with_period) = token_ends_with_period {
                 let prefix = token_ends_with_period.get(1).unwrap().as_str();
-                // Checks for 3 conditions if
+                let next = tokens.get(i + 1);
+                // Keeps the period attached if
                 // i.   the prefix contains a fullstop and
                 //      any char in the prefix is within the IsAlpha charset
-                // ii.  the prefix is in the list of nonbreaking prefixes and
-                //      does not contain #NUMERIC_ONLY#
-                // iii. the token is not the last token and that the
-                //      next token contains all lowercase.
+                // ii.  the prefix is a nonbreaking prefix that is not
+                //      #NUMERIC_ONLY# and the token is not the last one, the
+                //      final dot is split unconditionally
+                // iii. the next token starts with a lowercase letter
+                // iv.  the prefix is #NUMERIC_ONLY# and the next token
+                //      starts with a number
                 #[allow(clippy::if_same_then_else)]
                 if (prefix.chars().any(|c| c == '.') && MosesTokenizer::is_any_alpha(prefix))
-                    || (self.nonbreaking_prefixes.iter().any(|nbp| *nbp == prefix)
-                        && self.numeric_only_prefixes.iter().any(|nbp| *nbp == prefix))
-                    || (i != tokens.len() - 1
-                        && MosesTokenizer::is_lower(tokens.get(i + 1).unwrap()))
+                    || (next.is_some() && self.nonbreaking_prefixes.is_nonbreaking(prefix))
+                    || next.is_some_and(|next| MosesTokenizer::starts_lowercase(next))
                 {
-                    collector.push_str(*token);
-                } else if self.numeric_only_prefixes.iter().any(|nbp| *nbp == prefix)
-                    && (i + 1) < tokens.len()
-                    && RE_NUM.is_match(token)
+                    collector.push_str(token);
+                } else if self.nonbreaking_prefixes.is_numeric_only(prefix)
+                    && next.is_some_and(|next| RE_NUM.is_match(next))
                 {
-                    collector.push_str(*token);
+                    collector.push_str(token);
                 } else {
                     collector.push_str(prefix);
                     collector.push_str(" .");
                 }
             } else {
-                collector.push_str(*token);
+                collector.push_str(token);
             }
         });
 
         collector
     }
 
-    fn escape_xml(&self, text: &str) -> String {
-        lazy_static! {
-            static ref ESCAPE_XML_REGEXES: [(Regex, &'static str); 8] = [
-                (Regex::new(r"&").unwrap(), "&amp;"),
-                (Regex::new(r"\|").unwrap(), "&#124;"),
-                (Regex::new(r"<").unwrap(), "&lt;"),
-                (Regex::new(r">").unwrap(), "&gt;"),
-                (Regex::new("\"").unwrap(), "&quot;"),
-                (Regex::new(r"'").unwrap(), "&apos;"),
-                (Regex::new(r"\[").unwrap(), "&#91;"),
-                (Regex::new(r"]").unwrap(), "&#93;"),
-            ];
-        }
-
-        apply(Cow::Borrowed(text), ESCAPE_XML_REGEXES.iter())
-    }
-
-    pub fn penn_tokenize<T: Into<String>>(&self, text: T) -> Tokens {
+    pub fn penn_tokenize<T: Into<String>>(&self, text: T, escape: Option<bool>) -> Tokens {
         lazy_static! {
             static ref MOSES_PENN_REGEXES_1_0: [(Regex, &'static str); 19] = [
                 (Regex::new(r"\s+").unwrap(), " "),
@@ -740,7 +1287,7 @@ impl MosesTokenizer {
                 (Regex::new(r"([ (\[{<])'").unwrap(), r"$1 ` "),
                 (Regex::new(r"\.\.\.").unwrap(), r" _ELLIPSIS_ "),
                 (
-                    Regex::new(&*format!(
+                    Regex::new(&format!(
                         r"([^{}])[,]([^{}])",
                         PerlUniProps::IsN.as_str(),
                         PerlUniProps::IsN.as_str()
@@ -749,7 +1296,7 @@ impl MosesTokenizer {
                     r"$1 , $2"
                 ),
                 (
-                    Regex::new(&*format!(
+                    Regex::new(&format!(
                         r"([{}])[,]([^{}])",
                         PerlUniProps::IsN.as_str(),
                         PerlUniProps::IsN.as_str()
@@ -758,8 +1305,8 @@ impl MosesTokenizer {
                     r"$1 , $2"
                 ),
                 (
-                    Regex::new(&*format!(
-                        r"([^{}])[,]([^{}])",
+                    Regex::new(&format!(
+                        r"([^{}])[,]([{}])",
                         PerlUniProps::IsN.as_str(),
                         PerlUniProps::IsN.as_str()
                     ))
@@ -767,7 +1314,7 @@ impl MosesTokenizer {
                     r"$1 , $2"
                 ),
                 (
-                    Regex::new(&*format!(
+                    Regex::new(&format!(
                         r"([;:@#\$%&{}{}])",
                         PerlUniProps::IsSc.as_str(),
                         PerlUniProps::IsSo.as_str()
@@ -776,7 +1323,7 @@ impl MosesTokenizer {
                     r" $1"
                 ),
                 (
-                    Regex::new(&*format!(
+                    Regex::new(&format!(
                         r"([{}])/([{}])",
                         PerlUniProps::IsAlnum.as_str(),
                         PerlUniProps::IsAlnum.as_str()
@@ -791,67 +1338,74 @@ impl MosesTokenizer {
                 (Regex::new(r"([?!])").unwrap(), r" $1 "),
                 (Regex::new(r"([]\[(){}<>])").unwrap(), r" $1 "),
             ];
-            static ref MOSES_PENN_REGEXES_1_1: [(Regex, &'static str); 31] = [
+            static ref PENN_BRACKETS: [(Regex, &'static str); 6] = [
                 (Regex::new(r"\(").unwrap(), r"-LRB-"),
                 (Regex::new(r"\)").unwrap(), r"-RRB-"),
                 (Regex::new(r"\[").unwrap(), r"-LSB-"),
                 (Regex::new(r"\]").unwrap(), r"-RSB-"),
                 (Regex::new(r"\{").unwrap(), r"-LCB-"),
                 (Regex::new(r"\}").unwrap(), r"-RCB-"),
+            ];
+            static ref MOSES_PENN_REGEXES_1_1: [(Regex, &'static str); 24] = [
                 (Regex::new(r"--").unwrap(), r" -- "),
                 (Regex::new(r"^").unwrap(), r" "),
                 (Regex::new(r"$").unwrap(), r" "),
                 (Regex::new("\"").unwrap(), r" '' "),
-                (Regex::new(r"([^'])' ").unwrap(), r"$1 ' "),
-                (Regex::new(r"([^'])'").unwrap(), r"$1 ' "),
-                (Regex::new(r"'([sSmMdD]) ").unwrap(), r" '$1 "),
-                (Regex::new(r"'ll").unwrap(), r" 'll "),
-                (Regex::new(r"'re").unwrap(), r" 're "),
-                (Regex::new(r"'ve").unwrap(), r" 've "),
-                (Regex::new(r"n't").unwrap(), r" n't"),
-                (Regex::new(r"'LL").unwrap(), r" 'LL "),
-                (Regex::new(r"'RE").unwrap(), r" 'RE "),
-                (Regex::new(r"'VE").unwrap(), r" 'VE "),
-                (Regex::new(r"N'T").unwrap(), r" N'T "),
-                (Regex::new(r" ([Cc])annot ").unwrap(), r" $1an not "),
-                (Regex::new(r" ([Dd])'ye ").unwrap(), r" $1' ye "),
-                (Regex::new(r" ([Gg])imme ").unwrap(), r" $1im me "),
-                (Regex::new(r" ([Gg])onna ").unwrap(), r" $1on na "),
-                (Regex::new(r" ([Gg])otta ").unwrap(), r" $1ot ta "),
-                (Regex::new(r" ([Ll])emme ").unwrap(), r" $1em me "),
-                (Regex::new(r" ([Mm])ore'n ").unwrap(), r" $1ore 'n "),
-                (Regex::new(r" '([Tt])is ").unwrap(), r" '$1 is "),
-                (Regex::new(r" '([Tt])was ").unwrap(), r" '$1 was "),
-                (Regex::new(r" ([Ww])anna ").unwrap(), r" $1an na "),
+                (Regex::new(r"([^'])' ").unwrap(), r"${1} ' "),
+                (Regex::new(r"'([sSmMdD]) ").unwrap(), r" '${1} "),
+                (Regex::new(r"'ll ").unwrap(), r" 'll "),
+                (Regex::new(r"'re ").unwrap(), r" 're "),
+                (Regex::new(r"'ve ").unwrap(), r" 've "),
+                (Regex::new(r"n't ").unwrap(), r" n't "),
+                (Regex::new(r"'LL ").unwrap(), r" 'LL "),
+                (Regex::new(r"'RE ").unwrap(), r" 'RE "),
+                (Regex::new(r"'VE ").unwrap(), r" 'VE "),
+                (Regex::new(r"N'T ").unwrap(), r" N'T "),
+                (Regex::new(r" ([Cc])annot ").unwrap(), r" ${1}an not "),
+                (Regex::new(r" ([Dd])'ye ").unwrap(), r" ${1}' ye "),
+                (Regex::new(r" ([Gg])imme ").unwrap(), r" ${1}im me "),
+                (Regex::new(r" ([Gg])onna ").unwrap(), r" ${1}on na "),
+                (Regex::new(r" ([Gg])otta ").unwrap(), r" ${1}ot ta "),
+                (Regex::new(r" ([Ll])emme ").unwrap(), r" ${1}em me "),
+                (Regex::new(r" ([Mm])ore'n ").unwrap(), r" ${1}ore 'n "),
+                (Regex::new(r" '([Tt])is ").unwrap(), r" '${1} is "),
+                (Regex::new(r" '([Tt])was ").unwrap(), r" '${1} was "),
+                (Regex::new(r" ([Ww])anna ").unwrap(), r" ${1}an na "),
             ];
-            static ref MOSES_PENN_REGEXES_2: [(Regex, &'static str); 10] = [
+            static ref MOSES_PENN_REGEXES_2: [(Regex, &'static str); 4] = [
                 (Regex::new(r"_ELLIPSIS_").unwrap(), "..."),
                 (Regex::new(r"  *").unwrap(), r" "),
                 (Regex::new(r"^ *").unwrap(), r""),
                 (Regex::new(r" *$").unwrap(), r""),
-                (Regex::new(r"&").unwrap(), "&amp;"),
-                (Regex::new(r"\|").unwrap(), "&#124;"),
-                (Regex::new(r"<").unwrap(), "&lt;"),
-                (Regex::new(r">").unwrap(), "&gt;"),
-                (Regex::new("\"").unwrap(), "&quot;"),
-                (Regex::new(r"'").unwrap(), "&apos;"),
             ];
         }
 
         let text = text.into();
         let text = apply(Cow::Owned(text), MOSES_PENN_REGEXES_1_0.iter());
-        let text = self
-            .intratoken_slashes
-            .0
-            .replace_all(&*text, self.intratoken_slashes.1);
-        let text = apply(text, MOSES_PENN_REGEXES_1_1.iter());
-        let text = self.handles_nonbreaking_prefixes(&*text);
+        let text = Cow::Owned(self.split_between_alnums(&text, '/', " @/@ "));
+        let text = if self.options.penn_bracket_symbols {
+            apply(text, PENN_BRACKETS.iter())
+        } else {
+            text.into_owned()
+        };
+        let text = apply(Cow::Owned(text), MOSES_PENN_REGEXES_1_1.iter());
+        let text = self.handles_nonbreaking_prefixes(&text);
         let text = apply(Cow::Owned(text), MOSES_PENN_REGEXES_2.iter());
+        // Brackets are either rewritten to -LSB- etc. or asked for literally
+        let text = if escape.unwrap_or(self.options.escape) {
+            escape_xml_except_brackets(&text).into_owned()
+        } else {
+            text
+        };
 
-        Tokens { text }
+        Tokens::new(text)
     }
 
     pub fn tokenize<T: Into<String>>(&self, text: T, escape: Option<bool>) -> Tokens {
+        if self.options.penn {
+            return self.penn_tokenize(text, escape);
+        }
+
         let text = text.into();
         lazy_static! {
             static ref SPACE: Regex = Regex::new(r"\s+").unwrap();
@@ -859,44 +1413,93 @@ impl MosesTokenizer {
             static ref SPACE_END: Regex = Regex::new(r"\s*$").unwrap();
             static ref ASCII_JUNK: Regex = Regex::new(r"[\x00-\x1F]").unwrap();
         }
-        let text = SPACE.replace_all(&*text, " ");
+        let text = SPACE.replace_all(&text, " ");
         let text = ASCII_JUNK.replace_all(text.as_ref(), "");
 
-        // TODO Protected Patterns
+        let (text, protected_tokens) = if self.protected_patterns.is_empty() {
+            (text.into_owned(), Vec::new())
+        } else {
+            self.protect_patterns(&text)
+        };
 
         let text = SPACE_BEGIN.replace(text.as_ref(), "");
         let text = SPACE_END.replace(text.as_ref(), "");
 
-        // TODO Finnish and Swedish ["fi", "sv"] https://github.com/alvations/sacremoses/blob/b36fedeb15fb403ccc4f4ae61cbcd5c3d60e2883/sacremoses/tokenize.py#L469
-
-        let (regexp, substitution) = &self.pad_not_isalnum;
+        let (regexp, substitution) = &self.regexes.pad_not_isalnum;
         let text = regexp.replace_all(text.as_ref(), *substitution);
+        let text = match &self.regexes.cjk_char_split {
+            Some((regexp, substitution)) => {
+                Cow::Owned(regexp.replace_all(&text, *substitution).into_owned())
+            }
+            None => text,
+        };
 
-        // TODO aggressive dash splits as arg
+        let text = match self.lang {
+            Language::Fi | Language::Sv => {
+                lazy_static! {
+                    static ref LEADING_LOWERCASE: Regex = Regex::new(r"^\p{Ll}").unwrap();
+                }
 
-        let (regexp, substitution) = &self.aggressive_hyphen_split;
-        let text = regexp.replace_all(text.as_ref(), *substitution);
+                // Separate colons not immediately followed by lower-case characters
+                let mut padded = String::with_capacity(text.len());
+                let mut last = 0;
+                for (i, _) in text.match_indices(':') {
+                    if !LEADING_LOWERCASE.is_match(&text[i + 1..]) {
+                        padded.push_str(&text[last..i]);
+                        padded.push_str(" : ");
+                        last = i + 1;
+                    }
+                }
+                padded.push_str(&text[last..]);
+
+                Cow::Owned(padded)
+            }
+            _ => text,
+        };
+
+        let text = if self.options.aggressive_dash_splits {
+            Cow::Owned(self.split_between_alnums(&text, '-', " @-@ "))
+        } else {
+            text
+        };
+
+        let text = if self.options.intratoken_slashes {
+            lazy_static! {
+                static ref PAD_SLASH: Regex = Regex::new(r"@/@|/").unwrap();
+            }
+
+            let text = self.split_between_alnums(&text, '/', " @/@ ");
+            // Pad the slashes that were not split above
+            let text = PAD_SLASH.replace_all(&text, |caps: &Captures| match &caps[0] {
+                "/" => " / ",
+                _ => "@/@",
+            });
+
+            Cow::Owned(text.into_owned())
+        } else {
+            text
+        };
 
-        let text = self.replace_multidots(text.into_owned());
+        let (text, multidots) = self.replace_multidots(&text);
 
         lazy_static! {
             static ref COMMA_SEPARATE_1: Regex =
-                Regex::new(&*format!("([^{}])[,]", PerlUniProps::IsN.as_str())).unwrap();
+                Regex::new(&format!("([^{}])[,]", PerlUniProps::IsN.as_str())).unwrap();
             static ref COMMA_SEPARATE_2: Regex =
-                Regex::new(&*format!("[,]([^{}])", PerlUniProps::IsN.as_str())).unwrap();
+                Regex::new(&format!("[,]([^{}])", PerlUniProps::IsN.as_str())).unwrap();
             static ref COMMA_SEPARATE_3: Regex =
-                Regex::new(&*format!("([{}])[,]$", PerlUniProps::IsN.as_str())).unwrap();
+                Regex::new(&format!("([{}])[,]$", PerlUniProps::IsN.as_str())).unwrap();
         }
 
-        let text = COMMA_SEPARATE_1.replace_all(&*text, "$1 , ");
-        let text = COMMA_SEPARATE_2.replace_all(&*text, "$1 , ");
-        let text = COMMA_SEPARATE_3.replace_all(&*text, "$1 , ");
+        let text = COMMA_SEPARATE_1.replace_all(&text, "$1 , ");
+        let text = COMMA_SEPARATE_2.replace_all(&text, "$1 , ");
+        let text = COMMA_SEPARATE_3.replace_all(&text, "$1 , ");
 
         let text = if self.lang == Language::En {
             lazy_static! {
                 static ref ENGLISH_SPECIFIC_APOSTROPHE: [(Regex, &'static str); 5] = [
                     (
-                        Regex::new(&*format!(
+                        Regex::new(&format!(
                             r"([^{}])[']([^{}])",
                             PerlUniProps::IsAlpha.as_str(),
                             PerlUniProps::IsAlpha.as_str()
@@ -905,7 +1508,7 @@ impl MosesTokenizer {
                         r"$1 ' $2"
                     ),
                     (
-                        Regex::new(&*format!(
+                        Regex::new(&format!(
                             r"([^{}{}])[']([{}])",
                             PerlUniProps::IsAlpha.as_str(),
                             PerlUniProps::IsN.as_str(),
@@ -915,7 +1518,7 @@ impl MosesTokenizer {
                         r"$1 ' $2"
                     ),
                     (
-                        Regex::new(&*format!(
+                        Regex::new(&format!(
                             r"([{}])[']([^{}])",
                             PerlUniProps::IsAlpha.as_str(),
                             PerlUniProps::IsAlpha.as_str()
@@ -924,7 +1527,7 @@ impl MosesTokenizer {
                         r"$1 ' $2"
                     ),
                     (
-                        Regex::new(&*format!(
+                        Regex::new(&format!(
                             r"([{}])[']([{}])",
                             PerlUniProps::IsAlpha.as_str(),
                             PerlUniProps::IsAlpha.as_str()
@@ -933,7 +1536,7 @@ impl MosesTokenizer {
                         r"$1 '$2"
                     ),
                     (
-                        Regex::new(&*format!(r"([{}])[']([s])", PerlUniProps::IsN.as_str()))
+                        Regex::new(&format!(r"([{}])[']([s])", PerlUniProps::IsN.as_str()))
                             .unwrap(),
                         r"$1 '$2"
                     ),
@@ -944,7 +1547,7 @@ impl MosesTokenizer {
             lazy_static! {
                 static ref FR_IT_SPECIFIC_APOSTROPHE: [(Regex, &'static str); 4] = [
                     (
-                        Regex::new(&*format!(
+                        Regex::new(&format!(
                             r"([^{}])[']([^{}])",
                             PerlUniProps::IsAlpha.as_str(),
                             PerlUniProps::IsAlpha.as_str()
@@ -953,7 +1556,7 @@ impl MosesTokenizer {
                         r"$1 ' $2"
                     ),
                     (
-                        Regex::new(&*format!(
+                        Regex::new(&format!(
                             r"([^{}])[']([{}])",
                             PerlUniProps::IsAlpha.as_str(),
                             PerlUniProps::IsAlpha.as_str()
@@ -962,7 +1565,7 @@ impl MosesTokenizer {
                         r"$1 ' $2"
                     ),
                     (
-                        Regex::new(&*format!(
+                        Regex::new(&format!(
                             r"([{}])[']([^{}])",
                             PerlUniProps::IsAlpha.as_str(),
                             PerlUniProps::IsAlpha.as_str()
@@ -971,7 +1574,7 @@ impl MosesTokenizer {
                         r"$1 ' $2"
                     ),
                     (
-                        Regex::new(&*format!(
+                        Regex::new(&format!(
                             r"([{}])[']([{}])",
                             PerlUniProps::IsAlpha.as_str(),
                             PerlUniProps::IsAlpha.as_str()
@@ -986,9 +1589,9 @@ impl MosesTokenizer {
             text.into_owned()
         };
 
-        let text = self.handles_nonbreaking_prefixes(&*text);
+        let text = self.handles_nonbreaking_prefixes(&text);
 
-        let text: Cow<str> = SPACE.replace_all(&*text, " ");
+        let text: Cow<str> = SPACE.replace_all(&text, " ");
         let text: &str = text.trim();
 
         lazy_static! {
@@ -996,46 +1599,160 @@ impl MosesTokenizer {
         }
         let text = TRAILING_DOT_APOSTROPHE.replace_all(text, " . ' ");
 
-        // TODO restore protected patterns
+        let text = if protected_tokens.is_empty() {
+            text.into_owned()
+        } else {
+            self.restore_protected_patterns(&text, &protected_tokens)
+        };
 
-        let text = self.restore_multidots(text.into_owned());
+        let text = self.restore_multidots(&text, &multidots);
 
-        let text = if let Some(escape) = escape {
-            if escape {
-                self.escape_xml(&*text)
-            } else {
-                text
-            }
+        let text = if escape.unwrap_or(self.options.escape) {
+            escape_xml(&text)
         } else {
-            // Default: Do escape
-            self.escape_xml(&*text)
+            Cow::Owned(text)
         };
 
-        let text: Cow<str> = SPACE.replace_all(&*text, " ");
+        let text: Cow<str> = SPACE.replace_all(&text, " ");
         let text: &str = text.trim();
         let text: String = text.into();
 
-        Tokens { text }
+        Tokens::new(text)
     }
 }
 
+/// The output of a tokenizer: the tokens joined by single spaces, along with
+/// the byte range of every token in that text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Tokens {
-    pub text: String,
+    text: String,
+    boundaries: Vec<(usize, usize)>,
 }
 
 impl Tokens {
-    pub fn tokens(&self) -> Vec<&str> {
-        lazy_static! {
-            static ref SPACE: Regex = Regex::new(r"\s+").unwrap();
+    fn new(text: String) -> Tokens {
+        let mut boundaries = Vec::new();
+        let mut start = None;
+        for (i, c) in text.char_indices() {
+            match (start, c.is_whitespace()) {
+                (Some(token_start), true) => {
+                    boundaries.push((token_start, i));
+                    start = None;
+                }
+                (None, false) => start = Some(i),
+                _ => {}
+            }
+        }
+        if let Some(token_start) = start {
+            boundaries.push((token_start, text.len()));
+        }
+
+        Tokens { text, boundaries }
+    }
+
+    /// The tokens joined by single spaces.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    pub fn into_string(self) -> String {
+        self.text
+    }
+
+    pub fn len(&self) -> usize {
+        self.boundaries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.boundaries.is_empty()
+    }
+
+    pub fn iter(&self) -> TokensIter<'_> {
+        TokensIter {
+            text: &self.text,
+            boundaries: self.boundaries.iter(),
         }
+    }
 
-        SPACE.split(&*self.text).collect()
+    pub fn tokens(&self) -> Vec<&str> {
+        self.iter().collect()
+    }
+
+    pub fn into_strings(self) -> Vec<String> {
+        self.iter().map(String::from).collect()
+    }
+
+    /// The tokens with XML entities replaced by the characters they stand for.
+    pub fn unescaped(&self) -> Tokens {
+        Tokens::new(unescape_xml(&self.text).into_owned())
     }
 }
 
+impl Index<usize> for Tokens {
+    type Output = str;
+
+    fn index(&self, index: usize) -> &str {
+        let (start, end) = self.boundaries[index];
+
+        &self.text[start..end]
+    }
+}
+
+impl fmt::Display for Tokens {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl<'a> IntoIterator for &'a Tokens {
+    type Item = &'a str;
+    type IntoIter = TokensIter<'a>;
+
+    fn into_iter(self) -> TokensIter<'a> {
+        self.iter()
+    }
+}
+
+/// Iterator over the tokens of `Tokens`, see `Tokens::iter`.
+#[derive(Debug, Clone)]
+pub struct TokensIter<'a> {
+    text: &'a str,
+    boundaries: std::slice::Iter<'a, (usize, usize)>,
+}
+
+impl<'a> Iterator for TokensIter<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.boundaries
+            .next()
+            .map(|&(start, end)| &self.text[start..end])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.boundaries.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for TokensIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.boundaries
+            .next_back()
+            .map(|&(start, end)| &self.text[start..end])
+    }
+}
+
+impl ExactSizeIterator for TokensIter<'_> {}
+
 #[cfg(test)]
 mod tests {
-    use crate::{Language, MosesTokenizer};
+    use std::sync::Arc;
+    use std::thread;
+
+    use crate::{
+        unescape_xml, Error, Language, MosesPunctNormalizer, MosesTokenizer, MosesTokenizerOptions,
+        NonbreakingPrefixes, PerlUniProps, BASIC_PROTECTED_PATTERNS,
+    };
 
     #[test]
     fn test_1() {
@@ -1185,6 +1902,76 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_multidots() {
+        let tokenizer = MosesTokenizer::new(Language::En);
+
+        let tkns = tokenizer.tokenize("Wait.. what...? x..,y", None);
+        assert_eq!(
+            tkns.tokens(),
+            ["Wait", "..", "what", "...", "?", "x", "..", ",", "y"]
+        );
+
+        let tkns = tokenizer.tokenize("DOTMULTI and DOTDOTMULTI... stay", None);
+        assert_eq!(
+            tkns.tokens(),
+            ["DOTMULTI", "and", "DOTDOTMULTI", "...", "stay"]
+        );
+
+        let dots = ".".repeat(10_000);
+        let tkns = tokenizer.tokenize(format!("a{}b", dots), None);
+        assert_eq!(tkns.tokens(), ["a", dots.as_str(), "b"]);
+    }
+
+    #[test]
+    fn test_tokens() {
+        let tokenizer = MosesTokenizer::new(Language::En);
+
+        let tkns = tokenizer.tokenize("Tom & Jerry's  show!", None);
+        assert_eq!(tkns.len(), 6);
+        assert!(!tkns.is_empty());
+        assert_eq!(&tkns[1], "&amp;");
+        assert_eq!(tkns.iter().next_back(), Some("!"));
+        assert_eq!(tkns.to_string(), "Tom &amp; Jerry &apos;s show !");
+        assert_eq!(
+            (&tkns).into_iter().collect::<Vec<&str>>(),
+            ["Tom", "&amp;", "Jerry", "&apos;s", "show", "!"]
+        );
+        assert_eq!(
+            tkns.unescaped().into_strings(),
+            ["Tom", "&", "Jerry", "'s", "show", "!"]
+        );
+
+        for text in ["", " ", " \t\n "] {
+            let tkns = tokenizer.tokenize(text, None);
+            assert!(tkns.is_empty());
+            assert_eq!(tkns.len(), 0);
+            assert_eq!(tkns.as_str(), "");
+            assert!(tokenizer.penn_tokenize(text, None).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_split_between_alnums() {
+        let tokenizer = MosesTokenizer::builder(Language::Fi)
+            .intratoken_slashes(true)
+            .build()
+            .unwrap();
+
+        let tkns = tokenizer.tokenize("a-b-c a--b -a- x/y/z /a/ EU:ssa x::y", None);
+        assert_eq!(
+            tkns.tokens(),
+            [
+                "a", "@-@", "b", "@-@", "c", "a--b", "-a-", "x", "@/@", "y", "@/@", "z", "/", "a",
+                "/", "EU:ssa", "x", ":", ":y"
+            ]
+        );
+
+        let text = "a-".repeat(10_000);
+        let tkns = tokenizer.tokenize(text.as_str(), None);
+        assert_eq!(tkns.tokens().len(), 2 * 10_000 - 1);
+    }
+
     #[test]
     fn test_trailing_dot_apostrophe() {
         let text = "'Hello.'";
@@ -1224,4 +2011,405 @@ mod tests {
             ["Dvě", "děti", ",", "které", "běží", "bez", "bot", "."]
         );
     }
+
+    #[test]
+    fn test_protected_patterns() {
+        let tokenizer = MosesTokenizer::new(Language::En)
+            .with_protected_patterns(BASIC_PROTECTED_PATTERNS)
+            .unwrap();
+
+        let text = "this is a webpage https://stackoverflow.com/questions/6181381/how-to-print-variables-in-perl that kicks ass";
+        let tkns = tokenizer.tokenize(text, Option::None);
+        assert_eq!(
+            tkns.tokens(),
+            [
+                "this",
+                "is",
+                "a",
+                "webpage",
+                "https://stackoverflow.com/questions/6181381/how-to-print-variables-in-perl",
+                "that",
+                "kicks",
+                "ass"
+            ]
+        );
+
+        let text = "Mail foo.bar@example.co.uk, not <br/> me.";
+        let tkns = tokenizer.tokenize(text, Option::Some(false));
+        assert_eq!(
+            tkns.tokens(),
+            [
+                "Mail",
+                "foo.bar@example.co.uk",
+                ",",
+                "not",
+                "<br/>",
+                "me",
+                "."
+            ]
+        );
+
+        // Placeholders cannot be confused with the input
+        let text = "see THISISPROTECTED000 and http://a.com/b/c.html \u{2}0\u{2}";
+        let tkns = tokenizer.tokenize(text, None);
+        assert_eq!(
+            tkns.tokens(),
+            [
+                "see",
+                "THISISPROTECTED000",
+                "and",
+                "http://a.com/b/c.html",
+                "0"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_protected_patterns() {
+        let tokenizer = MosesTokenizer::new(Language::En)
+            .with_protected_patterns([r"[a-z]+-\d+/\d+"])
+            .unwrap();
+
+        let tkns = tokenizer.tokenize("Order SKU-12/34 today", Option::None);
+        assert_eq!(tkns.tokens(), ["Order", "SKU-12/34", "today"]);
+
+        assert!(MosesTokenizer::new(Language::En)
+            .with_protected_patterns(["("])
+            .is_err());
+    }
+
+    #[test]
+    fn test_fi_sv_colon() {
+        let tokenizer = MosesTokenizer::new(Language::Fi);
+        let tkns = tokenizer.tokenize("EU:n jäsenmaat kokoontuvat klo 12:00 Brysselissä:", None);
+        assert_eq!(
+            tkns.tokens(),
+            [
+                "EU:n",
+                "jäsenmaat",
+                "kokoontuvat",
+                "klo",
+                "12",
+                ":",
+                "00",
+                "Brysselissä",
+                ":"
+            ]
+        );
+
+        let tokenizer = MosesTokenizer::new(Language::Sv);
+        let tkns = tokenizer.tokenize("USA:s president besökte S:t Petersburg. Svar: Ja", None);
+        assert_eq!(
+            tkns.tokens(),
+            [
+                "USA:s",
+                "president",
+                "besökte",
+                "S:t",
+                "Petersburg",
+                ".",
+                "Svar",
+                ":",
+                "Ja"
+            ]
+        );
+
+        let tokenizer = MosesTokenizer::new(Language::En);
+        let tkns = tokenizer.tokenize("EU:n", None);
+        assert_eq!(tkns.tokens(), ["EU", ":", "n"]);
+    }
+
+    #[test]
+    fn test_builder_options() {
+        let tokenizer = MosesTokenizer::builder(Language::En)
+            .aggressive_dash_splits(false)
+            .intratoken_slashes(true)
+            .escape(false)
+            .build()
+            .unwrap();
+
+        let tkns = tokenizer.tokenize("A well-known and/or \"famous\" tool", None);
+        assert_eq!(
+            tkns.tokens(),
+            [
+                "A",
+                "well-known",
+                "and",
+                "@/@",
+                "or",
+                "\"",
+                "famous",
+                "\"",
+                "tool"
+            ]
+        );
+
+        let tkns = tokenizer.tokenize("it's", Some(true));
+        assert_eq!(tkns.tokens(), ["it", "&apos;s"]);
+
+        let tokenizer = MosesTokenizer::builder(Language::En)
+            .options(MosesTokenizerOptions {
+                penn: true,
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        assert!(tokenizer.options().penn);
+
+        let tkns = tokenizer.tokenize("Go (now) & stay", None);
+        assert_eq!(
+            tkns.tokens(),
+            ["Go", "-LRB-", "now", "-RRB-", "&amp;", "stay"]
+        );
+
+        let tokenizer = MosesTokenizer::builder(Language::En)
+            .penn(true)
+            .penn_bracket_symbols(false)
+            .build()
+            .unwrap();
+        let tkns = tokenizer.tokenize("Go (now) {x} [y] & z", None);
+        assert_eq!(
+            tkns.tokens(),
+            ["Go", "(", "now", ")", "{", "x", "}", "[", "y", "]", "&amp;", "z"]
+        );
+    }
+
+    #[test]
+    fn test_unescape_xml() {
+        let tokenizer = MosesTokenizer::new(Language::En);
+        let text = "A|B <c> [d] \"e\" & f's";
+
+        let tkns = tokenizer.tokenize(text, None);
+        assert_eq!(
+            tkns.as_str(),
+            "A &#124; B &lt; c &gt; &#91; d &#93; &quot; e &quot; &amp; f &apos;s"
+        );
+        assert_eq!(
+            tkns.unescaped().tokens(),
+            ["A", "|", "B", "<", "c", ">", "[", "d", "]", "\"", "e", "\"", "&", "f", "'s"]
+        );
+        assert_eq!(unescape_xml("&bar; &bra;&ket; &amp;lt;"), "| [] &lt;");
+
+        let tkns = tokenizer.penn_tokenize("Go (now) & <stay>", Some(false));
+        assert_eq!(
+            tkns.tokens(),
+            ["Go", "-LRB-", "now", "-RRB-", "&", "<", "stay", ">"]
+        );
+        let tkns = tokenizer.penn_tokenize("Go (now) & <stay>", None);
+        assert_eq!(
+            tkns.tokens(),
+            ["Go", "-LRB-", "now", "-RRB-", "&amp;", "&lt;", "stay", "&gt;"]
+        );
+    }
+
+    #[test]
+    fn test_penn_tokenize() {
+        let tokenizer = MosesTokenizer::new(Language::En);
+
+        let tkns = tokenizer.penn_tokenize("\"I'm gonna, can't you?\"", Some(false));
+        assert_eq!(
+            tkns.tokens(),
+            ["``", "I", "'m", "gon", "na", ",", "ca", "n't", "you", "?", "''"]
+        );
+
+        let tkns = tokenizer.penn_tokenize("It's, we'd, they'll wanna", Some(false));
+        assert_eq!(
+            tkns.tokens(),
+            ["It", "'s", ",", "we", "'d", ",", "they", "'ll", "wan", "na"]
+        );
+
+        let tkns = tokenizer.penn_tokenize("a,1 and 1,000", Some(false));
+        assert_eq!(tkns.tokens(), ["a", ",", "1", "and", "1,000"]);
+    }
+
+    #[test]
+    fn test_perluniprops_contains() {
+        for props in [
+            PerlUniProps::IsLower,
+            PerlUniProps::IsAlpha,
+            PerlUniProps::Han,
+        ] {
+            assert!(props.chars().all(|c| props.contains(c)));
+        }
+
+        assert!(PerlUniProps::IsLower.contains('ß'));
+        assert!(!PerlUniProps::IsLower.contains('A'));
+        assert!(PerlUniProps::IsAlpha.contains('λ'));
+        assert!(!PerlUniProps::IsAlpha.contains('1'));
+        assert!(!PerlUniProps::IsAlpha.contains('\n'));
+        assert!(PerlUniProps::Han.contains('中'));
+        assert!(!PerlUniProps::Han.contains('a'));
+    }
+
+    #[test]
+    fn test_normalize() {
+        let normalizer = MosesPunctNormalizer::new(Language::En);
+        assert_eq!(
+            normalizer.normalize("Hi (x) . 5 % of ``them'' said “it’s”, ok\u{a0}?"),
+            "Hi (x). 5% of \" them \" said 'it's', ok?"
+        );
+        assert_eq!(normalizer.normalize("1\u{a0}000 l‘ami"), "1.000 l'ami");
+
+        let normalizer = MosesPunctNormalizer::new(Language::De);
+        assert_eq!(
+            normalizer.normalize("„Er sagte…“, 1\u{a0}000"),
+            "\"Er sagte...', 1,000"
+        );
+    }
+
+    #[test]
+    fn test_fallible_constructors() {
+        assert_eq!(Language::from_code("EN").unwrap(), Language::En);
+        assert!(MosesTokenizer::try_new(Language::De).is_ok());
+        assert!(MosesPunctNormalizer::try_new(Language::De).is_ok());
+
+        assert_eq!(Language::from_code("cz").unwrap(), Language::Cs);
+        assert_eq!(Language::from_code("eng").unwrap(), Language::En);
+        let err = Language::from_code("enn").unwrap_err();
+        assert!(
+            matches!(&err, Error::UnknownLanguage { suggestions, .. } if suggestions == &["en"])
+        );
+        assert_eq!(
+            err.to_string(),
+            "unknown language code 'enn', did you mean 'en'?"
+        );
+        for code in ["", "-"] {
+            assert_eq!(
+                Language::from_tag(code).unwrap_err().to_string(),
+                format!("unknown language code '{}'", code)
+            );
+        }
+        let err = Language::from_code("qq").unwrap_err();
+        assert_eq!(err.to_string(), "unknown language code 'qq'");
+
+        let err = MosesTokenizer::builder(Language::En)
+            .protected_patterns(["(unclosed"])
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, Error::Regex(_)));
+        assert!(err.to_string().starts_with("failed to compile regex"));
+    }
+
+    #[test]
+    fn test_nonbreaking_prefixes() {
+        let tokenizer = MosesTokenizer::new(Language::En);
+        let tkns = tokenizer.tokenize("Mr. Smith read No. 5, not No. Six, see Abs. Two.", None);
+        assert_eq!(
+            tkns.tokens(),
+            [
+                "Mr.", "Smith", "read", "No.", "5", ",", "not", "No", ".", "Six", ",", "see",
+                "Abs", ".", "Two", "."
+            ]
+        );
+
+        let mut prefixes = NonbreakingPrefixes::for_language(Language::En);
+        prefixes.extend(["Abs", "Six #NUMERIC_ONLY#"]);
+        prefixes.remove("Mr");
+        let tokenizer = MosesTokenizer::builder(Language::En)
+            .nonbreaking_prefixes(prefixes)
+            .build()
+            .unwrap();
+        let tkns = tokenizer.tokenize("Mr. Smith read Abs. Two and Six. 7.", None);
+        assert_eq!(
+            tkns.tokens(),
+            ["Mr", ".", "Smith", "read", "Abs.", "Two", "and", "Six.", "7", "."]
+        );
+    }
+
+    #[test]
+    fn test_shared() {
+        let tokenizer = MosesTokenizer::shared(Language::En);
+        assert!(Arc::ptr_eq(
+            &tokenizer,
+            &MosesTokenizer::shared(Language::En)
+        ));
+        assert!(!Arc::ptr_eq(
+            &tokenizer,
+            &MosesTokenizer::shared(Language::De)
+        ));
+
+        // Tokenizers with the same language and options share their regexes
+        let other = MosesTokenizer::builder(Language::En)
+            .escape(false)
+            .build()
+            .unwrap();
+        assert!(Arc::ptr_eq(&tokenizer.regexes, &other.regexes));
+        let other = MosesTokenizer::builder(Language::En)
+            .intratoken_slashes(true)
+            .build()
+            .unwrap();
+        assert!(!Arc::ptr_eq(&tokenizer.regexes, &other.regexes));
+
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let tokenizer = MosesTokenizer::shared(Language::En);
+                thread::spawn(move || {
+                    tokenizer
+                        .tokenize(format!("Thread {}, go!", i), None)
+                        .into_string()
+                })
+            })
+            .collect();
+        for (i, handle) in handles.into_iter().enumerate() {
+            assert_eq!(handle.join().unwrap(), format!("Thread {} , go !", i));
+        }
+    }
+
+    #[test]
+    fn test_cjk() {
+        let tokenizer = MosesTokenizer::new(Language::Zh);
+        let tkns = tokenizer.tokenize("我爱北京天安门。你好！abc中文123", None);
+        assert_eq!(
+            tkns.tokens(),
+            ["我爱北京天安门", "。", "你好", "！", "abc中文123"]
+        );
+
+        let tokenizer = MosesTokenizer::new(Language::Ja);
+        let tkns = tokenizer.tokenize("東京へ行きます、「ラーメン」", None);
+        assert_eq!(
+            tkns.tokens(),
+            ["東京へ行きます", "、", "「", "ラーメン", "」"]
+        );
+
+        let tokenizer = MosesTokenizer::new(Language::Ko);
+        let tkns = tokenizer.tokenize("안녕하세요, 세계!", None);
+        assert_eq!(tkns.tokens(), ["안녕하세요", ",", "세계", "!"]);
+
+        let tokenizer = MosesTokenizer::builder(Language::Cjk)
+            .split_cjk_chars(true)
+            .build()
+            .unwrap();
+        let tkns = tokenizer.tokenize("中文、ラーメンと한국어 ok", None);
+        assert_eq!(
+            tkns.tokens(),
+            ["中", "文", "、", "ラ", "ー", "メ", "ン", "と", "한", "국", "어", "ok"]
+        );
+    }
+
+    #[test]
+    fn test_language_tags() {
+        assert_eq!(Language::from_tag("en-US").unwrap(), Language::En);
+        assert_eq!(Language::from_tag("pt_BR").unwrap(), Language::Pt);
+        assert_eq!(Language::from_tag("zh-Hant").unwrap(), Language::Zh);
+        assert_eq!(Language::from_tag("ces").unwrap(), Language::Cs);
+        assert_eq!(Language::from_tag("CZ").unwrap(), Language::Cs);
+        assert_eq!(Language::from_tag("yue-HK").unwrap(), Language::Yue);
+        assert_eq!(
+            Language::from_tag("xx-YY").unwrap_err().to_string(),
+            "unknown language code 'xx-YY'"
+        );
+
+        assert!(Language::all().all(|lang| lang != Language::Cz));
+        assert_eq!(Language::all().count(), 40);
+        assert_eq!(Language::De.code(), "de");
+        assert_eq!(Language::Ga.name(), "Irish");
+        assert!(Language::Cz.has_nonbreaking_prefixes());
+        assert!(!Language::Ja.has_nonbreaking_prefixes());
+
+        let tokenizer = MosesTokenizer::new(Language::Cz);
+        assert_eq!(
+            tokenizer.tokenize("Napsal Ing. Novák.", None).tokens(),
+            ["Napsal", "Ing.", "Novák", "."]
+        );
+    }
 }
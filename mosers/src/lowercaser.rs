@@ -0,0 +1,157 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::Language;
+
+fn is_upper_vowel(c: char) -> bool {
+    matches!(c, 'A' | 'E' | 'I' | 'O' | 'U' | 'Á' | 'É' | 'Í' | 'Ó' | 'Ú')
+}
+
+/// Combining marks of class 230 (above) from the Combining Diacritical
+/// Marks block, the ones that matter for Lithuanian.
+fn is_mark_above(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0300}'..='\u{0314}'
+            | '\u{033D}'..='\u{0344}'
+            | '\u{0346}'
+            | '\u{034A}'..='\u{034C}'
+            | '\u{0350}'..='\u{0352}'
+            | '\u{0357}'
+            | '\u{035B}'
+            | '\u{0363}'..='\u{036F}'
+    )
+}
+
+/// Lithuanian keeps the dot of `i` and `j` when an accent is placed above,
+/// see the `lt` rules of Unicode's SpecialCasing.txt.
+fn lowercase_lithuanian(text: &str) -> String {
+    let mut lowercased = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            'Ì' => lowercased.push_str("i\u{0307}\u{0300}"),
+            'Í' => lowercased.push_str("i\u{0307}\u{0301}"),
+            'Ĩ' => lowercased.push_str("i\u{0307}\u{0303}"),
+            'I' | 'J' | 'Į' if chars.peek().is_some_and(|&next| is_mark_above(next)) => {
+                lowercased.extend(c.to_lowercase());
+                lowercased.push('\u{0307}');
+            }
+            _ => lowercased.extend(c.to_lowercase()),
+        }
+    }
+
+    lowercased
+}
+
+/// Irish writes the `n` and `t` mutations before a capitalized vowel without
+/// hyphen, e.g. `nAthair`, which becomes `n-athair` in lowercase.
+fn lowercase_irish(text: &str) -> String {
+    let mut lowercased = String::with_capacity(text.len() + 4);
+    let mut chars = text.chars().peekable();
+    let mut previous = None;
+    while let Some(c) = chars.next() {
+        lowercased.extend(c.to_lowercase());
+        if matches!(c, 'n' | 't')
+            && !previous.is_some_and(char::is_alphanumeric)
+            && chars.peek().is_some_and(|&next| is_upper_vowel(next))
+        {
+            lowercased.push('-');
+        }
+        previous = Some(c);
+    }
+
+    lowercased
+}
+
+/// Port of Moses' `lowercase.perl` following Unicode casing rules, with the
+/// tailorings for Lithuanian and Irish. Greek sigma becomes `ς` at the end of
+/// a word and `σ` elsewhere.
+#[derive(Debug, Clone)]
+pub struct MosesLowercaser {
+    lang: Language,
+    preserve_entities: bool,
+}
+
+impl MosesLowercaser {
+    pub fn new(lang: Language) -> MosesLowercaser {
+        MosesLowercaser {
+            lang,
+            preserve_entities: false,
+        }
+    }
+
+    /// Leaves XML entities such as `&APOS;` or `&#91;` untouched instead of
+    /// lowercasing them with the text. Entities also end words, so a sigma
+    /// right before one is final.
+    pub fn preserve_entities(mut self, preserve: bool) -> MosesLowercaser {
+        self.preserve_entities = preserve;
+        self
+    }
+
+    pub fn lowercase(&self, text: &str) -> String {
+        lazy_static! {
+            static ref ENTITY: Regex =
+                Regex::new(r"&(?:[[:alpha:]]+|#[0-9]+|#[xX][[:xdigit:]]+);").unwrap();
+        }
+
+        if !self.preserve_entities {
+            return self.lowercase_text(text);
+        }
+
+        let mut lowercased = String::with_capacity(text.len());
+        let mut last = 0;
+        for entity in ENTITY.find_iter(text) {
+            lowercased.push_str(&self.lowercase_text(&text[last..entity.start()]));
+            lowercased.push_str(entity.as_str());
+            last = entity.end();
+        }
+        lowercased.push_str(&self.lowercase_text(&text[last..]));
+
+        lowercased
+    }
+
+    fn lowercase_text(&self, text: &str) -> String {
+        match self.lang {
+            Language::Lt => lowercase_lithuanian(text),
+            Language::Ga => lowercase_irish(text),
+            // Handles the context dependent final sigma, e.g. for Greek
+            _ => text.to_lowercase(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Language, MosesLowercaser};
+
+    #[test]
+    fn test_lowercase() {
+        let greek = MosesLowercaser::new(Language::El);
+        assert_eq!(greek.lowercase("ΟΔΟΣ ΣΟΦΙΑΣ ."), "οδος σοφιας .");
+        assert_eq!(greek.lowercase("Σ"), "σ");
+        assert_eq!(
+            greek.lowercase("ΟΔΟΣ&quot; ΟΔΟΣ&APOS;"),
+            "οδος&quot; οδος&apos;"
+        );
+        let greek = greek.preserve_entities(true);
+        assert_eq!(
+            greek.lowercase("ΟΔΟΣ&APOS;Α &#91; &AMP;"),
+            "οδος&APOS;α &#91; &AMP;"
+        );
+
+        let lithuanian = MosesLowercaser::new(Language::Lt);
+        assert_eq!(
+            lithuanian.lowercase("ÌĮ\u{0301}I"),
+            "i\u{0307}\u{0300}į\u{0307}\u{0301}i"
+        );
+        assert_eq!(MosesLowercaser::new(Language::En).lowercase("Ì"), "ì");
+
+        let irish = MosesLowercaser::new(Language::Ga);
+        assert_eq!(
+            irish.lowercase("ár nAthair , an tUisce , Ná nÓ nIarann nósanna"),
+            "ár n-athair , an t-uisce , ná n-ó n-iarann nósanna"
+        );
+        assert_eq!(irish.lowercase("Ante tAcht"), "ante t-acht");
+    }
+}
@@ -0,0 +1,308 @@
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, BufWriter, LineWriter, Write};
+use std::process;
+
+use mosers::{
+    unescape_xml, Language, MosesDetokenizer, MosesDetruecaser, MosesLowercaser,
+    MosesPunctNormalizer, MosesSentenceSplitter, MosesTokenizer, MosesTruecaser,
+};
+
+const USAGE: &str = "Usage: mosers <command> [options] < input > output
+
+Commands (options follow the Moses perl scripts):
+  tokenize         -l LANG [-a] [-no-escape] [-penn] [-x] [-protected FILE] [-b]
+  detokenize       -l LANG [-penn] [-b]
+  deescape         [-b]
+  lowercase        [-l LANG] [-b]
+  normalize        -l LANG [-penn] [-b]
+  split-sentences  -l LANG [-b]
+  train-truecaser  -model FILE [-corpus FILE]
+  truecase         -model FILE [-b]
+  detruecase       [-b]";
+
+#[derive(Debug, Default, PartialEq)]
+struct Args {
+    command: String,
+    lang: Option<String>,
+    aggressive: bool,
+    no_escape: bool,
+    penn: bool,
+    skip_xml: bool,
+    protected: Option<String>,
+    model: Option<String>,
+    corpus: Option<String>,
+    unbuffered: bool,
+}
+
+impl Args {
+    fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Args, String> {
+        let mut args = args.into_iter();
+        let mut parsed = Args {
+            command: args.next().ok_or_else(|| USAGE.to_string())?,
+            ..Default::default()
+        };
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .ok_or_else(|| format!("missing value for {}", name))
+            };
+            // Accept both the perl style `-penn` and `--penn`
+            match arg.trim_start_matches('-') {
+                "l" => parsed.lang = Some(value("-l")?),
+                "a" => parsed.aggressive = true,
+                "no-escape" => parsed.no_escape = true,
+                "penn" => parsed.penn = true,
+                "x" => parsed.skip_xml = true,
+                "protected" => parsed.protected = Some(value("-protected")?),
+                "model" => parsed.model = Some(value("-model")?),
+                "corpus" => parsed.corpus = Some(value("-corpus")?),
+                "b" => parsed.unbuffered = true,
+                // Quiet mode: nothing is written to stderr anyway
+                "q" => {}
+                "threads" | "lines" => {
+                    value(&arg)?;
+                }
+                "h" | "help" => return Err(USAGE.to_string()),
+                _ => return Err(format!("unknown option {}\n\n{}", arg, USAGE)),
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    fn language(&self) -> Result<Language, String> {
+        let lang = self.lang.as_deref().unwrap_or("en");
+
+        Language::from_tag(lang).map_err(|err| err.to_string())
+    }
+
+    fn model(&self) -> Result<&str, String> {
+        self.model
+            .as_deref()
+            .ok_or_else(|| format!("{} requires -model FILE", self.command))
+    }
+}
+
+/// The normalizer of normalize-punctuation.perl, which neither replaces
+/// unicode punctuation nor removes control characters. Its `-penn` flag
+/// skips the `` ` `` and `''` rewrite that is applied by default.
+fn punct_normalizer(lang: Language, penn: bool) -> MosesPunctNormalizer {
+    MosesPunctNormalizer::new_with_options(lang, Some(!penn), None, None, Some(false), Some(false))
+}
+
+fn for_each_line<R, W, F>(input: R, mut output: W, mut process: F) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+    F: FnMut(&str) -> String,
+{
+    for line in input.lines() {
+        writeln!(output, "{}", process(&line?))?;
+    }
+
+    output.flush()
+}
+
+fn split_sentences<R: BufRead, W: Write>(
+    splitter: &MosesSentenceSplitter,
+    input: R,
+    mut output: W,
+) -> io::Result<()> {
+    let mut paragraph = String::new();
+    let flush_paragraph = |paragraph: &mut String, output: &mut W| {
+        for sentence in splitter.split(paragraph) {
+            writeln!(output, "{}", sentence)?;
+        }
+        paragraph.clear();
+
+        Ok::<(), io::Error>(())
+    };
+
+    // Paragraphs are separated by empty lines or single XML tags on a line
+    for line in input.lines() {
+        let line = line?;
+        let trimmed = line.trim();
+        let is_tag = trimmed.starts_with('<') && trimmed.ends_with('>');
+        if trimmed.is_empty() || is_tag {
+            let had_text = !paragraph.is_empty();
+            flush_paragraph(&mut paragraph, &mut output)?;
+            if is_tag {
+                writeln!(output, "{}", trimmed)?;
+            } else if had_text {
+                writeln!(output, "<P>")?;
+            }
+        } else {
+            paragraph.push(' ');
+            paragraph.push_str(trimmed);
+        }
+    }
+    flush_paragraph(&mut paragraph, &mut output)?;
+
+    output.flush()
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let stdin = io::stdin();
+    let input = stdin.lock();
+    let output = io::stdout().lock();
+    // Unbuffered output is flushed after every line
+    let output: Box<dyn Write> = if args.unbuffered {
+        Box::new(LineWriter::new(output))
+    } else {
+        Box::new(BufWriter::new(output))
+    };
+
+    let result = match args.command.as_str() {
+        "tokenize" => {
+            let mut builder = MosesTokenizer::builder(args.language()?)
+                .aggressive_dash_splits(args.aggressive)
+                .escape(!args.no_escape)
+                .penn(args.penn);
+            if let Some(protected) = &args.protected {
+                let patterns = fs::read_to_string(protected).map_err(|err| err.to_string())?;
+                builder = builder.protected_patterns(patterns.lines().filter(|p| !p.is_empty()));
+            }
+            let tokenizer = builder.build().map_err(|err| err.to_string())?;
+
+            tokenizer.tokenize_reader(input, output, None, args.skip_xml)
+        }
+        "detokenize" => {
+            let detokenizer = MosesDetokenizer::new(args.language()?);
+
+            for_each_line(input, output, |line| {
+                if args.penn {
+                    detokenizer.penn_detokenize(line.split_whitespace(), None)
+                } else {
+                    detokenizer.detokenize(line.split_whitespace(), None)
+                }
+            })
+        }
+        "deescape" => for_each_line(input, output, |line| unescape_xml(line).into_owned()),
+        "lowercase" => {
+            let lowercaser = MosesLowercaser::new(args.language()?);
+
+            for_each_line(input, output, |line| lowercaser.lowercase(line))
+        }
+        "normalize" => {
+            let normalizer = punct_normalizer(args.language()?, args.penn);
+
+            normalizer.normalize_reader(input, output)
+        }
+        "split-sentences" => {
+            let splitter = MosesSentenceSplitter::new(args.language()?);
+
+            split_sentences(&splitter, input, output)
+        }
+        "train-truecaser" => {
+            let model = args.model()?;
+            let corpus: Box<dyn BufRead> = match &args.corpus {
+                Some(corpus) => Box::new(io::BufReader::new(
+                    fs::File::open(corpus).map_err(|err| err.to_string())?,
+                )),
+                None => Box::new(input),
+            };
+            // Stream the corpus, keeping the first read error to report it
+            let mut error = None;
+            let sentences = corpus
+                .lines()
+                .map_while(|line| line.map_err(|err| error = Some(err)).ok())
+                .map(|line| {
+                    line.split_whitespace()
+                        .map(str::to_string)
+                        .collect::<Vec<_>>()
+                });
+            let truecaser = MosesTruecaser::train(sentences, false);
+            if let Some(err) = error {
+                return Err(err.to_string());
+            }
+
+            truecaser.save_to_file(model)
+        }
+        "truecase" => {
+            let truecaser =
+                MosesTruecaser::load_from_file(args.model()?).map_err(|err| err.to_string())?;
+
+            for_each_line(input, output, |line| {
+                truecaser.truecase(line.split_whitespace()).join(" ")
+            })
+        }
+        "detruecase" => {
+            let detruecaser = MosesDetruecaser::new();
+
+            for_each_line(input, output, |line| {
+                detruecaser
+                    .detruecase(line.split_whitespace(), false)
+                    .join(" ")
+            })
+        }
+        command => return Err(format!("unknown command {}\n\n{}", command, USAGE)),
+    };
+
+    match result {
+        // Closing the pipe early, e.g. with `head`, is not an error
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => result.map_err(|err| err.to_string()),
+    }
+}
+
+fn main() {
+    let result = Args::parse(env::args().skip(1)).and_then(run);
+
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{punct_normalizer, split_sentences, Args};
+    use mosers::{Language, MosesSentenceSplitter};
+
+    fn args(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_perl_style_args() {
+        let parsed = args(&["tokenize", "-l", "de", "-a", "-no-escape", "-threads", "4"]).unwrap();
+
+        assert_eq!(parsed.command, "tokenize");
+        assert_eq!(parsed.language(), Ok(Language::De));
+        assert!(parsed.aggressive && parsed.no_escape && !parsed.penn && !parsed.skip_xml);
+        assert!(args(&["tokenize", "-l", "en", "-x"]).unwrap().skip_xml);
+
+        assert!(args(&["tokenize", "-l"]).is_err());
+        assert!(args(&["tokenize", "-frobnicate"]).is_err());
+        assert!(args(&["tokenize", "-l", "xx"]).unwrap().language().is_err());
+    }
+
+    #[test]
+    fn test_line_processing() {
+        let splitter = MosesSentenceSplitter::new(Language::En);
+        let mut output = Vec::new();
+        split_sentences(
+            &splitter,
+            "This is one. This is\ntwo.\n\n<doc>\nThree!\n".as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "This is one.\nThis is two.\n<P>\n<doc>\nThree!\n"
+        );
+    }
+
+    #[test]
+    fn test_normalize_penn_flag() {
+        let text = "``Hi'' , `you'";
+
+        let normalizer = punct_normalizer(Language::En, false);
+        assert_eq!(normalizer.normalize(text), " \" Hi \" , 'you'");
+
+        let normalizer = punct_normalizer(Language::En, true);
+        assert_eq!(normalizer.normalize(text), "``Hi\" , `you'");
+    }
+}
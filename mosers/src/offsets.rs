@@ -0,0 +1,257 @@
+use std::ops::Range;
+
+use crate::{unescape_xml, MosesTokenizer, Tokens};
+
+/// Location of a token in the original input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenSpan {
+    pub bytes: Range<usize>,
+    pub chars: Range<usize>,
+}
+
+/// Strings a token may have been produced from, most likely first.
+fn surface_forms(token: &str) -> Vec<String> {
+    let mut forms = vec![token.to_string()];
+    let unescaped = unescape_xml(token);
+    if unescaped != token {
+        forms.push(unescaped.into_owned());
+    }
+
+    let special: &[&str] = match forms.last().unwrap().as_str() {
+        "@-@" => &["-"],
+        "@/@" => &["/"],
+        "-LRB-" => &["("],
+        "-RRB-" => &[")"],
+        "-LSB-" => &["["],
+        "-RSB-" => &["]"],
+        "-LCB-" => &["{"],
+        "-RCB-" => &["}"],
+        "``" => &["\"", "'"],
+        "`" => &["'"],
+        "''" => &["\""],
+        _ => &[],
+    };
+    forms.extend(special.iter().map(|form| form.to_string()));
+
+    forms
+}
+
+/// Whitespace and the control characters `\x00`-`\x1F`, which the tokenizer
+/// drops between tokens.
+fn is_skippable(c: char) -> bool {
+    c.is_whitespace() || c <= '\x1F'
+}
+
+/// Length in bytes of the prefix of `text` that reads as `form` once the
+/// control characters `\x00`-`\x1F`, which the tokenizer strips, are left out.
+fn match_len(text: &str, form: &str) -> Option<usize> {
+    let mut form = form.chars().peekable();
+    let mut len = 0;
+    for c in text.chars() {
+        if form.peek().is_none() {
+            break;
+        }
+        if c <= '\x1F' {
+            len += c.len_utf8();
+            continue;
+        }
+        if form.next() != Some(c) {
+            return None;
+        }
+        len += c.len_utf8();
+    }
+
+    form.peek().is_none().then_some(len)
+}
+
+struct Aligner<'a> {
+    text: &'a str,
+    byte: usize,
+    char: usize,
+    // The previous token could not be aligned, so its text may still lie
+    // ahead of the cursor
+    lost: bool,
+}
+
+impl<'a> Aligner<'a> {
+    fn advance_to(&mut self, byte: usize) {
+        self.char += self.text[self.byte..byte].chars().count();
+        self.byte = byte;
+    }
+
+    fn skip_separators(&mut self) {
+        let skipped: usize = self.text[self.byte..]
+            .chars()
+            .take_while(|c| is_skippable(*c))
+            .map(char::len_utf8)
+            .sum();
+        self.advance_to(self.byte + skipped);
+    }
+
+    fn consume(&mut self, len: usize) -> TokenSpan {
+        let (byte_start, char_start) = (self.byte, self.char);
+        self.advance_to(self.byte + len);
+
+        TokenSpan {
+            bytes: byte_start..self.byte,
+            chars: char_start..self.char,
+        }
+    }
+
+    fn match_at(&self, byte: usize, forms: &[String]) -> Option<usize> {
+        forms
+            .iter()
+            .find_map(|form| match_len(&self.text[byte..], form))
+    }
+
+    fn align(&mut self, token: &str) -> Option<TokenSpan> {
+        self.skip_separators();
+        let forms = surface_forms(token);
+
+        if let Some(len) = self.match_at(self.byte, &forms) {
+            self.lost = false;
+            return Some(self.consume(len));
+        }
+
+        // Only skip ahead over the text of a token that could not be aligned,
+        // never over text that might belong to this one
+        if self.lost {
+            let next = self.text[self.byte..]
+                .char_indices()
+                .map(|(at, _)| self.byte + at)
+                .find_map(|at| self.match_at(at, &forms).map(|len| (at, len)));
+            if let Some((at, len)) = next {
+                self.lost = false;
+                self.advance_to(at);
+                return Some(self.consume(len));
+            }
+        }
+
+        self.lost = true;
+        None
+    }
+}
+
+impl MosesTokenizer {
+    /// Tokenizes `text` like `tokenize` and returns, for every token, its
+    /// span in `text`, or `None` if the token was rewritten in a way that
+    /// cannot be traced back to the input.
+    pub fn tokenize_with_offsets(
+        &self,
+        text: &str,
+        escape: Option<bool>,
+    ) -> (Tokens, Vec<Option<TokenSpan>>) {
+        let tokens = self.tokenize(text, escape);
+        let mut aligner = Aligner {
+            text,
+            byte: 0,
+            char: 0,
+            lost: false,
+        };
+        let spans = tokens.iter().map(|token| aligner.align(token)).collect();
+
+        (tokens, spans)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Aligner;
+    use crate::{Language, MosesTokenizer, TokenSpan};
+
+    #[test]
+    fn test_offsets() {
+        let tokenizer = MosesTokenizer::new(Language::En);
+        let text = "Ça  coûte 5€... isn't it?";
+        let (tkns, spans) = tokenizer.tokenize_with_offsets(text, None);
+
+        let tokens = tkns.tokens();
+        assert_eq!(
+            tokens,
+            ["Ça", "coûte", "5", "€", "...", "isn", "&apos;t", "it", "?"]
+        );
+        assert_eq!(spans.len(), tokens.len());
+        let surfaces: Vec<&str> = spans
+            .iter()
+            .map(|span| &text[span.as_ref().unwrap().bytes.clone()])
+            .collect();
+        assert_eq!(
+            surfaces,
+            ["Ça", "coûte", "5", "€", "...", "isn", "'t", "it", "?"]
+        );
+
+        let chars: Vec<char> = text.chars().collect();
+        let surfaces: Vec<String> = spans
+            .iter()
+            .map(|span| chars[span.as_ref().unwrap().chars.clone()].iter().collect())
+            .collect();
+        assert_eq!(
+            surfaces,
+            ["Ça", "coûte", "5", "€", "...", "isn", "'t", "it", "?"]
+        );
+    }
+
+    #[test]
+    fn test_offsets_rewritten_tokens() {
+        let tokenizer = MosesTokenizer::builder(Language::En)
+            .penn(true)
+            .build()
+            .unwrap();
+        let text = "\u{1}(a-b) & \"c d";
+        let (tkns, spans) = tokenizer.tokenize_with_offsets(text, None);
+
+        assert_eq!(
+            tkns.tokens(),
+            ["-LRB-", "a-b", "-RRB-", "&amp;", "``", "c", "d"]
+        );
+        let surfaces: Vec<&str> = spans
+            .iter()
+            .map(|span| &text[span.as_ref().unwrap().bytes.clone()])
+            .collect();
+        assert_eq!(surfaces, ["(", "a-b", ")", "&", "\"", "c", "d"]);
+
+        let tokenizer = MosesTokenizer::new(Language::En);
+        let (tkns, spans) = tokenizer.tokenize_with_offsets(" \t ", None);
+        assert!(tkns.is_empty());
+        assert!(spans.is_empty());
+    }
+
+    #[test]
+    fn test_offsets_unaligned() {
+        let tokenizer = MosesTokenizer::new(Language::En);
+        let text = "sto\u{1}p it";
+        let (tkns, spans) = tokenizer.tokenize_with_offsets(text, None);
+        assert_eq!(tkns.tokens(), ["stop", "it"]);
+        let surfaces: Vec<&str> = spans
+            .iter()
+            .map(|span| &text[span.as_ref().unwrap().bytes.clone()])
+            .collect();
+        assert_eq!(surfaces, ["sto\u{1}p", "it"]);
+
+        let text = "co.\u{7f}x";
+        let (tkns, spans) = tokenizer.tokenize_with_offsets(text, None);
+        assert_eq!(tkns.tokens(), ["co", ".", "\u{7f}", "x"]);
+        let surfaces: Vec<&str> = spans
+            .iter()
+            .map(|span| &text[span.as_ref().unwrap().bytes.clone()])
+            .collect();
+        assert_eq!(surfaces, ["co", ".", "\u{7f}", "x"]);
+
+        // A token the input does not contain, e.g. from a rewriting rule
+        let mut aligner = Aligner {
+            text: "a b c b",
+            byte: 0,
+            char: 0,
+            lost: false,
+        };
+        let spans: Vec<Option<TokenSpan>> = ["a", "x", "c", "y", "b"]
+            .iter()
+            .map(|token| aligner.align(token))
+            .collect();
+        let bytes: Vec<_> = spans
+            .into_iter()
+            .map(|span| span.map(|span| span.bytes))
+            .collect();
+        assert_eq!(bytes, [Some(0..1), None, Some(4..5), None, Some(6..7)]);
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use crate::Language;
+
+const NUMERIC_ONLY: &str = "#NUMERIC_ONLY#";
+
+/// Words that do not end a sentence when followed by a period, in the
+/// format of Moses' `nonbreaking_prefix.*` files.
+///
+/// Entries marked with `#NUMERIC_ONLY#` are only nonbreaking when the next
+/// word is a number, e.g. `No. 5`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NonbreakingPrefixes {
+    prefixes: HashSet<String>,
+    numeric_only: HashSet<String>,
+}
+
+impl NonbreakingPrefixes {
+    /// An empty set.
+    pub fn new() -> NonbreakingPrefixes {
+        NonbreakingPrefixes::default()
+    }
+
+    /// The list bundled for `lang`.
+    pub fn for_language(lang: Language) -> NonbreakingPrefixes {
+        let lines: Vec<&'static str> = lang.into();
+
+        lines.into_iter().collect()
+    }
+
+    /// Reads a list in the Moses format: one prefix per line, `#` starts a
+    /// comment line.
+    pub fn load<R: BufRead>(reader: R) -> io::Result<NonbreakingPrefixes> {
+        let mut prefixes = NonbreakingPrefixes::new();
+        for line in reader.lines() {
+            prefixes.insert(&line?);
+        }
+
+        Ok(prefixes)
+    }
+
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> io::Result<NonbreakingPrefixes> {
+        NonbreakingPrefixes::load(BufReader::new(File::open(path)?))
+    }
+
+    /// Adds a line of a Moses prefix file, like `Dr` or `No #NUMERIC_ONLY#`.
+    /// Comments and empty lines are ignored.
+    pub fn insert(&mut self, line: &str) {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return;
+        }
+
+        match line.split_once(NUMERIC_ONLY) {
+            Some((prefix, _)) => {
+                let prefix = prefix.trim();
+                self.prefixes.remove(prefix);
+                self.numeric_only.insert(prefix.to_string());
+            }
+            None => {
+                self.numeric_only.remove(line);
+                self.prefixes.insert(line.to_string());
+            }
+        }
+    }
+
+    /// Removes a prefix, whether it is numeric only or not. Returns whether
+    /// it was present.
+    pub fn remove(&mut self, prefix: &str) -> bool {
+        let prefix = prefix.trim();
+        let removed = self.prefixes.remove(prefix);
+
+        self.numeric_only.remove(prefix) || removed
+    }
+
+    /// Whether `prefix` never ends a sentence.
+    pub fn is_nonbreaking(&self, prefix: &str) -> bool {
+        self.prefixes.contains(prefix)
+    }
+
+    /// Whether `prefix` does not end a sentence when followed by a number.
+    pub fn is_numeric_only(&self, prefix: &str) -> bool {
+        self.numeric_only.contains(prefix)
+    }
+
+    pub fn len(&self) -> usize {
+        self.prefixes.len() + self.numeric_only.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<S: AsRef<str>> Extend<S> for NonbreakingPrefixes {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, lines: I) {
+        for line in lines {
+            self.insert(line.as_ref());
+        }
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for NonbreakingPrefixes {
+    fn from_iter<I: IntoIterator<Item = S>>(lines: I) -> NonbreakingPrefixes {
+        let mut prefixes = NonbreakingPrefixes::new();
+        prefixes.extend(lines);
+
+        prefixes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Language, NonbreakingPrefixes};
+
+    #[test]
+    fn test_prefixes() {
+        let mut prefixes = NonbreakingPrefixes::for_language(Language::En);
+        assert!(prefixes.is_nonbreaking("Mr"));
+        assert!(prefixes.is_numeric_only("No"));
+        assert!(!prefixes.is_nonbreaking("No"));
+
+        prefixes.extend(["Abs", "Art", "Dr.med", "Fig #NUMERIC_ONLY#"]);
+        assert!(prefixes.is_nonbreaking("Art"));
+        assert!(!prefixes.is_numeric_only("Art"));
+        assert!(prefixes.is_numeric_only("Fig"));
+        assert!(prefixes.remove("Mr"));
+        assert!(!prefixes.remove("Mr"));
+        assert!(!prefixes.is_nonbreaking("Mr"));
+
+        let loaded =
+            NonbreakingPrefixes::load("# legal\nAbs\n\n  Nr #NUMERIC_ONLY# # numbers\n".as_bytes())
+                .unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert!(loaded.is_nonbreaking("Abs"));
+        assert!(loaded.is_numeric_only("Nr"));
+    }
+}
@@ -0,0 +1,238 @@
+use std::ops::Range;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::{Language, NonbreakingPrefixes, PerlUniProps};
+
+/// Port of Moses' `split-sentences.perl`.
+#[derive(Debug, Clone)]
+pub struct MosesSentenceSplitter {
+    lang: Language,
+    nonbreaking_prefixes: NonbreakingPrefixes,
+}
+
+impl MosesSentenceSplitter {
+    pub fn new(lang: Language) -> MosesSentenceSplitter {
+        MosesSentenceSplitter {
+            lang,
+            nonbreaking_prefixes: NonbreakingPrefixes::for_language(lang),
+        }
+    }
+
+    /// Replaces the bundled nonbreaking prefixes of the language.
+    pub fn with_nonbreaking_prefixes(
+        mut self,
+        prefixes: NonbreakingPrefixes,
+    ) -> MosesSentenceSplitter {
+        self.nonbreaking_prefixes = prefixes;
+        self
+    }
+
+    /// Splits a paragraph into sentences.
+    pub fn split<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        self.split_spans(text)
+            .into_iter()
+            .map(|span| &text[span])
+            .collect()
+    }
+
+    /// Splits a paragraph into sentences and returns their byte ranges in
+    /// `text`. Whitespace around sentences is not part of the spans.
+    pub fn split_spans(&self, text: &str) -> Vec<Range<usize>> {
+        let mut breaks = self.punctuation_breaks(text);
+        breaks.extend(self.period_breaks(text));
+        breaks.sort_by_key(|gap| gap.start);
+
+        let mut spans = Vec::new();
+        let mut start = 0;
+        for gap in breaks
+            .into_iter()
+            .chain(std::iter::once(text.len()..text.len()))
+        {
+            if gap.start < start {
+                continue;
+            }
+            let sentence = &text[start..gap.start];
+            let trimmed = sentence.trim_start();
+            let begin = start + (sentence.len() - trimmed.len());
+            let end = begin + trimmed.trim_end().len();
+            if begin < end {
+                spans.push(begin..end);
+            }
+            start = gap.end;
+        }
+
+        spans
+    }
+
+    /// Breaks found by the regex rules, as ranges of the whitespace between
+    /// two sentences.
+    fn punctuation_breaks(&self, text: &str) -> Vec<Range<usize>> {
+        lazy_static! {
+            // Digits only start a sentence after a period, see `period_breaks`
+            static ref SENTENCE_START: String = format!("[{}]", PerlUniProps::IsUpper.as_str());
+            static ref OPENING: String = format!("['\"(\\[¿¡{}]", PerlUniProps::IsPi.as_str());
+            static ref RULES: [Regex; 4] = [
+                // Non-period end of sentence markers followed by sentence starters
+                Regex::new(&format!(
+                    r"([?!\x{{0964}}\x{{0965}}])\s+({}*{})",
+                    *OPENING, *SENTENCE_START
+                ))
+                .unwrap(),
+                // Multi-dots followed by sentence starters
+                Regex::new(&format!(r"(\.\.+)\s+({}*{})", *OPENING, *SENTENCE_START)).unwrap(),
+                // Punctuation inside a quote or parenthetical
+                Regex::new(&format!(
+                    "([?!.\\x{{0964}}\\x{{0965}}] *[\\x{{300d}}\\x{{300f}}'\")\\]{}]+)\\s+({}* *{})",
+                    PerlUniProps::IsPf.as_str(),
+                    *OPENING,
+                    *SENTENCE_START
+                ))
+                .unwrap(),
+                // Punctuation followed by opening punctuation and a sentence starter
+                Regex::new(&format!(
+                    "([?!.\\x{{0964}}\\x{{0965}}])\\s+([\\x{{300d}}\\x{{300f}}'\"(\\[¿¡{}]+ *{})",
+                    PerlUniProps::IsPi.as_str(),
+                    *SENTENCE_START
+                ))
+                .unwrap(),
+            ];
+            // These are not followed by whitespace
+            static ref CJK_FULL_STOP: Regex = Regex::new(r"[。．？！♪]").unwrap();
+        }
+
+        let mut breaks: Vec<Range<usize>> = RULES
+            .iter()
+            .flat_map(|rule| rule.captures_iter(text))
+            .map(|caps| caps.get(1).unwrap().end()..caps.get(2).unwrap().start())
+            .collect();
+
+        if matches!(
+            self.lang,
+            Language::Zh | Language::Yue | Language::Ja | Language::Cjk
+        ) {
+            breaks.extend(
+                CJK_FULL_STOP
+                    .find_iter(text)
+                    .map(|full_stop| full_stop.end()..full_stop.end()),
+            );
+        }
+
+        breaks
+    }
+
+    /// Breaks after words ending in periods, unless they are known
+    /// nonbreaking prefixes or acronyms.
+    fn period_breaks(&self, text: &str) -> Vec<Range<usize>> {
+        lazy_static! {
+            static ref WORD: Regex = Regex::new(r"\S+").unwrap();
+            static ref ENDS_WITH_PERIOD: Regex = Regex::new(&format!(
+                "([{}.\\-]*)(['\")\\]%{}]*)(\\.+)$",
+                PerlUniProps::IsAlnum.as_str(),
+                PerlUniProps::IsPf.as_str()
+            ))
+            .unwrap();
+            static ref UPPER_ACRONYM: Regex = Regex::new(&format!(
+                r"(\.)[{}\-]+(\.+)$",
+                PerlUniProps::IsUpper.as_str()
+            ))
+            .unwrap();
+            static ref NEXT_STARTS_SENTENCE: Regex = Regex::new(&format!(
+                "^['\"(\\[¿¡{}]*[0-9{}]",
+                PerlUniProps::IsPi.as_str(),
+                PerlUniProps::IsUpper.as_str()
+            ))
+            .unwrap();
+            static ref NUMBER: Regex = Regex::new(r"^[0-9]+").unwrap();
+        }
+
+        let words: Vec<regex::Match> = WORD.find_iter(text).collect();
+        words
+            .windows(2)
+            .filter_map(|pair| {
+                let (word, next) = (pair[0].as_str(), pair[1].as_str());
+                let caps = ENDS_WITH_PERIOD.captures(word)?;
+                let prefix = caps.get(1).unwrap().as_str();
+                let starting_punct = caps.get(2).unwrap().as_str();
+
+                if !prefix.is_empty()
+                    && starting_punct.is_empty()
+                    && self.nonbreaking_prefixes.is_nonbreaking(prefix)
+                {
+                    // Known honorific, never break
+                    return None;
+                }
+                if UPPER_ACRONYM.is_match(word) || !NEXT_STARTS_SENTENCE.is_match(next) {
+                    return None;
+                }
+                if !prefix.is_empty()
+                    && starting_punct.is_empty()
+                    && self.nonbreaking_prefixes.is_numeric_only(prefix)
+                    && NUMBER.is_match(next)
+                {
+                    return None;
+                }
+
+                Some(pair[0].end()..pair[1].start())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Language, MosesSentenceSplitter};
+
+    #[test]
+    fn test_split() {
+        let splitter = MosesSentenceSplitter::new(Language::En);
+
+        assert_eq!(
+            splitter
+                .split("Mr. Smith went to Washington. He saw the U.S. Capitol! Was it big? Yes."),
+            [
+                "Mr. Smith went to Washington.",
+                "He saw the U.S. Capitol!",
+                "Was it big?",
+                "Yes."
+            ]
+        );
+        assert_eq!(
+            splitter
+                .split("See No. 5 on the list. \"Really?\" she asked. (It was.) Then... Nothing."),
+            [
+                "See No. 5 on the list.",
+                "\"Really?\" she asked.",
+                "(It was.)",
+                "Then...",
+                "Nothing."
+            ]
+        );
+        assert_eq!(
+            splitter.split("It ends with No. Then a new one."),
+            ["It ends with No.", "Then a new one."]
+        );
+        assert_eq!(
+            splitter.split("Is it 5? 6 more. 7 left."),
+            ["Is it 5? 6 more.", "7 left."]
+        );
+        assert!(splitter.split(" \n ").is_empty());
+    }
+
+    #[test]
+    fn test_split_spans() {
+        let splitter = MosesSentenceSplitter::new(Language::De);
+        let text = "  Das ist z.B. gut.\nDas\tauch!  ";
+
+        let spans = splitter.split_spans(text);
+        assert_eq!(spans, [2..19, 20..29]);
+        assert_eq!(&text[spans[1].clone()], "Das\tauch!");
+
+        let splitter = MosesSentenceSplitter::new(Language::Zh);
+        assert_eq!(
+            splitter.split("我爱北京。你呢？好！"),
+            ["我爱北京。", "你呢？", "好！"]
+        );
+    }
+}
@@ -0,0 +1,204 @@
+use std::io::{self, BufRead, Write};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::{MosesPunctNormalizer, MosesTokenizer, Tokens};
+
+/// Calls `process` for every line of `reader`, without its line ending,
+/// and writes the results one per line. The line buffer is reused, so
+/// memory stays bounded by the longest line.
+fn for_each_line<R, W, F>(mut reader: R, mut writer: W, mut process: F) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+    F: FnMut(&str) -> String,
+{
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 {
+        let text = line.strip_suffix('\n').unwrap_or(&line);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        writer.write_all(process(text).as_bytes())?;
+        writer.write_all(b"\n")?;
+        line.clear();
+    }
+
+    writer.flush()
+}
+
+impl MosesTokenizer {
+    /// Tokenizes `reader` line by line into `writer`, one tokenized line per
+    /// input line. Wrap unbuffered writers such as files in a `BufWriter`.
+    ///
+    /// With `skip_xml`, lines holding a single XML tag are written unchanged,
+    /// like `tokenizer.perl -x`.
+    pub fn tokenize_reader<R: BufRead, W: Write>(
+        &self,
+        reader: R,
+        writer: W,
+        escape: Option<bool>,
+        skip_xml: bool,
+    ) -> io::Result<()> {
+        lazy_static! {
+            static ref XML_LINE: Regex = Regex::new(r"^<.+>$").unwrap();
+        }
+
+        for_each_line(reader, writer, |line| {
+            if skip_xml && XML_LINE.is_match(line) {
+                line.to_string()
+            } else {
+                self.tokenize(line, escape).into_string()
+            }
+        })
+    }
+}
+
+impl MosesPunctNormalizer {
+    /// Normalizes `reader` line by line into `writer`, one normalized line
+    /// per input line. Wrap unbuffered writers such as files in a `BufWriter`.
+    pub fn normalize_reader<R: BufRead, W: Write>(&self, reader: R, writer: W) -> io::Result<()> {
+        for_each_line(reader, writer, |line| self.normalize(line))
+    }
+}
+
+/// Lazily tokenizes the lines of an iterator, see
+/// `MosesIteratorExt::tokenize_with`.
+pub struct TokenizeWith<'a, I> {
+    lines: I,
+    tokenizer: &'a MosesTokenizer,
+}
+
+impl<I> Iterator for TokenizeWith<'_, I>
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    type Item = Tokens;
+
+    fn next(&mut self) -> Option<Tokens> {
+        self.lines
+            .next()
+            .map(|line| self.tokenizer.tokenize(line.as_ref(), None))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.lines.size_hint()
+    }
+}
+
+/// Lazily normalizes the lines of an iterator, see
+/// `MosesIteratorExt::normalize_with`.
+pub struct NormalizeWith<'a, I> {
+    lines: I,
+    normalizer: &'a MosesPunctNormalizer,
+}
+
+impl<I> Iterator for NormalizeWith<'_, I>
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.lines
+            .next()
+            .map(|line| self.normalizer.normalize(line.as_ref()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.lines.size_hint()
+    }
+}
+
+/// Iterator adaptors processing one line at a time, e.g.
+/// `lines.normalize_with(&normalizer).tokenize_with(&tokenizer)`.
+pub trait MosesIteratorExt: Iterator + Sized
+where
+    Self::Item: AsRef<str>,
+{
+    fn tokenize_with(self, tokenizer: &MosesTokenizer) -> TokenizeWith<'_, Self> {
+        TokenizeWith {
+            lines: self,
+            tokenizer,
+        }
+    }
+
+    fn normalize_with(self, normalizer: &MosesPunctNormalizer) -> NormalizeWith<'_, Self> {
+        NormalizeWith {
+            lines: self,
+            normalizer,
+        }
+    }
+}
+
+impl<I> MosesIteratorExt for I
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Language, MosesIteratorExt, MosesPunctNormalizer, MosesTokenizer};
+
+    #[test]
+    fn test_tokenize_reader() {
+        let tokenizer = MosesTokenizer::new(Language::En);
+        let mut output = Vec::new();
+        tokenizer
+            .tokenize_reader(
+                "Hello, world!\r\n\nIt's me.".as_bytes(),
+                &mut output,
+                None,
+                false,
+            )
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Hello , world !\n\nIt &apos;s me .\n"
+        );
+
+        let text = "<doc id=\"1\">\nHi, <b>you</b>\n<3 you >\n</doc>\n";
+        let mut output = Vec::new();
+        tokenizer
+            .tokenize_reader(text.as_bytes(), &mut output, None, false)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "&lt; doc id = &quot; 1 &quot; &gt;\nHi , &lt; b &gt; you &lt; / b &gt;\n\
+             &lt; 3 you &gt;\n&lt; / doc &gt;\n"
+        );
+        let mut output = Vec::new();
+        tokenizer
+            .tokenize_reader(text.as_bytes(), &mut output, None, true)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "<doc id=\"1\">\nHi , &lt; b &gt; you &lt; / b &gt;\n<3 you >\n</doc>\n"
+        );
+
+        let normalizer = MosesPunctNormalizer::new(Language::En);
+        let mut output = Vec::new();
+        normalizer
+            .normalize_reader("a  «b»\nc ( d ) e\n".as_bytes(), &mut output)
+            .unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "a \"b\"\nc (d) e\n");
+    }
+
+    #[test]
+    fn test_iterator_adaptors() {
+        let tokenizer = MosesTokenizer::new(Language::En);
+        let normalizer = MosesPunctNormalizer::new(Language::En);
+        let lines = ["«Hi»,  you", "it ( works )"];
+
+        let tokenized: Vec<String> = lines
+            .iter()
+            .normalize_with(&normalizer)
+            .tokenize_with(&tokenizer)
+            .map(|tokens| tokens.into_string())
+            .collect();
+        assert_eq!(tokenized, ["&quot; Hi , &quot; you", "it ( works )"]);
+    }
+}
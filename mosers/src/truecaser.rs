@@ -0,0 +1,377 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::{PerlUniProps, Tokens};
+
+const SENT_END: [&str; 4] = [".", ":", "?", "!"];
+const DELAYED_SENT_START: [&str; 8] = ["(", "[", "\"", "'", "&apos;", "&quot;", "&#91;", "&#93;"];
+
+fn is_xml_tag(token: &str) -> bool {
+    lazy_static! {
+        static ref XML_TAG: Regex = Regex::new(r"<\S[^>]*>").unwrap();
+    }
+
+    XML_TAG.is_match(token)
+}
+
+fn has_cased_letter(token: &str) -> bool {
+    token.chars().any(|c| {
+        PerlUniProps::IsLower.contains(c)
+            || PerlUniProps::IsUpper.contains(c)
+            || PerlUniProps::TitlecaseLetter.contains(c)
+    })
+}
+
+fn starts_lowercase(token: &str) -> bool {
+    token
+        .chars()
+        .next()
+        .is_some_and(|c| PerlUniProps::IsLower.contains(c))
+}
+
+fn upper_first(token: &str) -> String {
+    let mut chars = token.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Truecasing model as written by Moses' `train-truecaser.perl`.
+#[derive(Debug, Clone, Default)]
+pub struct MosesTruecaser {
+    casing: HashMap<String, HashMap<String, f64>>,
+    best: HashMap<String, String>,
+    known: HashSet<String>,
+}
+
+impl MosesTruecaser {
+    pub fn new() -> MosesTruecaser {
+        MosesTruecaser::default()
+    }
+
+    /// Trains a model on tokenized sentences. Sentence-initial tokens are
+    /// ignored unless `possibly_use_first_token` is set, in which case a
+    /// lowercase first token still counts.
+    pub fn train<I, D, S>(sentences: I, possibly_use_first_token: bool) -> MosesTruecaser
+    where
+        I: IntoIterator<Item = D>,
+        D: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut casing: HashMap<String, HashMap<String, f64>> = HashMap::new();
+        for sentence in sentences {
+            let mut is_first_word = true;
+            for (i, token) in sentence.into_iter().enumerate() {
+                let token = token.as_ref();
+                if is_xml_tag(token) || DELAYED_SENT_START.contains(&token) {
+                    continue;
+                }
+                if !is_first_word && SENT_END.contains(&token) {
+                    is_first_word = true;
+                    continue;
+                }
+                if !has_cased_letter(token) {
+                    is_first_word = false;
+                    continue;
+                }
+
+                let weight =
+                    if !is_first_word || (possibly_use_first_token && starts_lowercase(token)) {
+                        1.0
+                    } else if possibly_use_first_token && i == 1 {
+                        0.1
+                    } else {
+                        0.0
+                    };
+                is_first_word = false;
+
+                if weight > 0.0 {
+                    *casing
+                        .entry(token.to_lowercase())
+                        .or_default()
+                        .entry(token.to_string())
+                        .or_insert(0.0) += weight;
+                }
+            }
+        }
+
+        MosesTruecaser::from_casing(casing)
+    }
+
+    fn from_casing(casing: HashMap<String, HashMap<String, f64>>) -> MosesTruecaser {
+        let mut best = HashMap::new();
+        let mut known = HashSet::new();
+        for (lowercase, forms) in &casing {
+            if let Some((form, _)) = MosesTruecaser::sorted_forms(forms).first() {
+                best.insert(lowercase.clone(), form.to_string());
+            }
+            known.extend(forms.keys().cloned());
+        }
+
+        MosesTruecaser {
+            casing,
+            best,
+            known,
+        }
+    }
+
+    /// Casing variants of one word, most frequent first.
+    fn sorted_forms(forms: &HashMap<String, f64>) -> Vec<(&str, f64)> {
+        let mut forms: Vec<(&str, f64)> = forms
+            .iter()
+            .map(|(form, count)| (form.as_str(), *count))
+            .collect();
+        forms.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+        forms
+    }
+
+    /// Reads a model in the Moses `truecase-model` format, where each line
+    /// reads `best (count/total) other (count) ...`.
+    pub fn load<R: BufRead>(reader: R) -> io::Result<MosesTruecaser> {
+        let invalid = |line: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid truecase model line: {:?}", line),
+            )
+        };
+
+        let mut truecaser = MosesTruecaser::new();
+        for line in reader.lines() {
+            let line = line?;
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.is_empty() {
+                continue;
+            }
+
+            let mut forms = HashMap::new();
+            for pair in fields.chunks(2) {
+                let count = match pair.get(1) {
+                    Some(count) => count
+                        .trim_start_matches('(')
+                        .trim_end_matches(')')
+                        .split('/')
+                        .next()
+                        .and_then(|count| count.parse::<f64>().ok())
+                        .ok_or_else(|| invalid(&line))?,
+                    // Bare words are valid, they just carry no counts
+                    None => 0.0,
+                };
+                truecaser.known.insert(pair[0].to_string());
+                forms.insert(pair[0].to_string(), count);
+            }
+            // The first word of each line is the best form
+            let lowercase = fields[0].to_lowercase();
+            truecaser
+                .best
+                .insert(lowercase.clone(), fields[0].to_string());
+            truecaser.casing.insert(lowercase, forms);
+        }
+
+        Ok(truecaser)
+    }
+
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> io::Result<MosesTruecaser> {
+        MosesTruecaser::load(BufReader::new(File::open(path)?))
+    }
+
+    /// Writes the model in the Moses `truecase-model` format.
+    pub fn save<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let mut lowercases: Vec<&String> = self.casing.keys().collect();
+        lowercases.sort();
+
+        for lowercase in lowercases {
+            let forms = &self.casing[lowercase];
+            let best = &self.best[lowercase];
+            let total: f64 = forms.values().sum();
+            write!(writer, "{} ({}/{})", best, forms[best], total)?;
+            for (form, count) in MosesTruecaser::sorted_forms(forms) {
+                if form != best {
+                    write!(writer, " {} ({})", form, count)?;
+                }
+            }
+            writeln!(writer)?;
+        }
+
+        writer.flush()
+    }
+
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.save(BufWriter::new(File::create(path)?))
+    }
+
+    /// Restores the most likely casing of tokenized text.
+    pub fn truecase<I, S>(&self, tokens: I) -> Vec<String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut is_first_word = true;
+        tokens
+            .into_iter()
+            .map(|token| {
+                let token = token.as_ref();
+                // XML tags and lone factor separators are kept as they are
+                if is_xml_tag(token) || token.starts_with('|') {
+                    return token.to_string();
+                }
+
+                let (word, factors) = match token.find('|') {
+                    Some(at) => token.split_at(at),
+                    None => (token, ""),
+                };
+                let best = self.best.get(&word.to_lowercase());
+                let word = match best {
+                    Some(best) if is_first_word => best.as_str(),
+                    _ if self.known.contains(word) => word,
+                    Some(best) => best.as_str(),
+                    None => word,
+                };
+
+                if SENT_END.contains(&word) {
+                    is_first_word = true;
+                } else if !DELAYED_SENT_START.contains(&word) {
+                    is_first_word = false;
+                }
+
+                format!("{}{}", word, factors)
+            })
+            .collect()
+    }
+
+    pub fn truecase_tokens(&self, tokens: &Tokens) -> Tokens {
+        Tokens::new(self.truecase(tokens).join(" "))
+    }
+}
+
+const ALWAYS_LOWER: &[&str] = &[
+    "a", "after", "against", "al.-", "and", "any", "as", "at", "be", "because", "between", "by",
+    "both", "but", "ch.-", "doesn't", "don't", "e.g.", "for", "from", "had", "has", "have", "he",
+    "how", "however", "i.e.", "if", "in", "into", "is", "it", "its", "may", "more", "most", "nor",
+    "not", "of", "on", "or", "per", "shall", "should", "since", "so", "some", "than", "that",
+    "the", "these", "they", "this", "to", "up", "was", "were", "when", "which", "who", "whom",
+    "whose", "why", "will", "with", "would",
+];
+
+/// Port of Moses' `detruecase.perl`.
+#[derive(Debug, Clone, Default)]
+pub struct MosesDetruecaser {}
+
+impl MosesDetruecaser {
+    pub fn new() -> MosesDetruecaser {
+        MosesDetruecaser {}
+    }
+
+    /// Uppercases sentence-initial tokens. Headlines are title-cased, except
+    /// for function words.
+    pub fn detruecase<I, S>(&self, tokens: I, is_headline: bool) -> Vec<String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut sentence_start = true;
+        tokens
+            .into_iter()
+            .map(|token| {
+                let token = token.as_ref();
+                let cased = if sentence_start || (is_headline && !ALWAYS_LOWER.contains(&token)) {
+                    upper_first(token)
+                } else {
+                    token.to_string()
+                };
+
+                if SENT_END.contains(&token) {
+                    sentence_start = true;
+                } else if !DELAYED_SENT_START.contains(&token) {
+                    sentence_start = false;
+                }
+
+                cased
+            })
+            .collect()
+    }
+
+    pub fn detruecase_tokens(&self, tokens: &Tokens, is_headline: bool) -> Tokens {
+        Tokens::new(self.detruecase(tokens, is_headline).join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Language, MosesDetruecaser, MosesTokenizer, MosesTruecaser};
+
+    fn corpus() -> Vec<Vec<String>> {
+        let tokenizer = MosesTokenizer::new(Language::En);
+        [
+            "The Apple iPhone was announced in Paris .",
+            "Paris is the capital of France , the iPhone said .",
+            "the apple fell far from the tree .",
+            "An apple a day keeps the doctor away . The doctor agrees .",
+            "Doctor Who visited Paris .",
+            "I met Doctor Who .",
+        ]
+        .iter()
+        .map(|line| tokenizer.tokenize(*line, None).into_strings())
+        .collect()
+    }
+
+    #[test]
+    fn test_train_and_truecase() {
+        let truecaser = MosesTruecaser::train(corpus(), false);
+
+        assert_eq!(
+            truecaser.truecase(["The", "Doctor", "saw", "An", "APPLE", "in", "paris", "."]),
+            ["the", "Doctor", "saw", "An", "apple", "in", "Paris", "."]
+        );
+        assert_eq!(
+            truecaser.truecase(["&quot;", "The", "iphone", ".", "Paris", "!"]),
+            ["&quot;", "the", "iPhone", ".", "Paris", "!"]
+        );
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let truecaser = MosesTruecaser::train(corpus(), false);
+        let mut model = Vec::new();
+        truecaser.save(&mut model).unwrap();
+        let model = String::from_utf8(model).unwrap();
+
+        assert!(model.contains("doctor (2/3) Doctor (1)\n"));
+
+        let loaded = MosesTruecaser::load(model.as_bytes()).unwrap();
+        let tokens = ["The", "DOCTOR", "visited", "PARIS", "."];
+        assert_eq!(loaded.truecase(tokens), truecaser.truecase(tokens));
+
+        let loaded =
+            MosesTruecaser::load("Paris (3/3)\nmcdonald (1/2) McDonald (1)\n".as_bytes()).unwrap();
+        assert_eq!(
+            loaded.truecase(["PARIS", "MCDONALD", "McDonald"]),
+            ["Paris", "mcdonald", "McDonald"]
+        );
+
+        assert!(MosesTruecaser::load("Paris (x/3)\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_detruecase() {
+        let detruecaser = MosesDetruecaser::new();
+
+        assert_eq!(
+            detruecaser.detruecase(
+                ["the", "apple", "fell", ".", "&quot;", "it", "hurt", "&quot;"],
+                false
+            ),
+            ["The", "apple", "fell", ".", "&quot;", "It", "hurt", "&quot;"]
+        );
+        assert_eq!(
+            detruecaser.detruecase(["the", "lord", "of", "the", "rings"], true),
+            ["The", "Lord", "of", "the", "Rings"]
+        );
+    }
+}
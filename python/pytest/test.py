@@ -1,4 +1,5 @@
 import mosers
+import pytest
 
 
 def test_1():
@@ -171,3 +172,66 @@ def test_final_dot_unconditionally():
     tkns = tokenizer.tokenize("Dvě děti, které běží bez bot.")
     expected_tkns = ["Dvě", "děti", ",", "které", "běží", "bez", "bot", "."]
     assert expected_tkns == tkns
+
+
+def test_tokenizer_options():
+    tokenizer = mosers.MosesTokenizer("en", aggressive_dash_splits=False, intratoken_slashes=True, escape=False)
+
+    tkns = tokenizer.tokenize("A well-known and/or 'famous' tool")
+    expected_tkns = ["A", "well-known", "and", "@/@", "or", "'", "famous", "'", "tool"]
+    assert expected_tkns == tkns
+
+    tkns = tokenizer.tokenize("A well-known tool", return_str=True)
+    assert "A well-known tool" == tkns
+
+
+def test_unescape_xml():
+    tokenizer = mosers.MosesTokenizer("en")
+
+    tkns = tokenizer.tokenize("Tom & Jerry's")
+    assert ["Tom", "&amp;", "Jerry", "&apos;s"] == tkns
+    assert ["Tom", "&", "Jerry", "'s"] == [mosers.unescape_xml(tkn) for tkn in tkns]
+
+    tkns = tokenizer.penn_tokenize("Go (now) & stay", False)
+    assert ["Go", "-LRB-", "now", "-RRB-", "&", "stay"] == tkns
+
+
+def test_penn_bracket_symbols():
+    tokenizer = mosers.MosesTokenizer("en", penn_bracket_symbols=False)
+
+    tkns = tokenizer.penn_tokenize("I'm gonna go (now)", False)
+    assert ["I", "'m", "gon", "na", "go", "(", "now", ")"] == tkns
+
+    tkns = tokenizer.penn_tokenize("[a] & b")
+    assert ["[", "a", "]", "&amp;", "b"] == tkns
+
+
+def test_cjk():
+    tokenizer = mosers.MosesTokenizer("zh")
+    assert ["我爱北京", "。"] == tokenizer.tokenize("我爱北京。")
+
+    tokenizer = mosers.MosesTokenizer("zh", split_cjk_chars=True)
+    assert ["我", "爱", "北", "京", "。"] == tokenizer.tokenize("我爱北京。")
+
+
+def test_empty_input():
+    tokenizer = mosers.MosesTokenizer("en")
+
+    assert [] == tokenizer.tokenize("")
+    assert [] == tokenizer.tokenize(" \t ")
+    assert "" == tokenizer.tokenize(" ", return_str=True)
+
+
+def test_unknown_language():
+    with pytest.raises(ValueError, match="did you mean 'en'"):
+        mosers.MosesTokenizer("engl")
+
+    with pytest.raises(ValueError, match="unknown language code 'xx'"):
+        mosers.MosesPunctNormalizer("xx")
+
+
+def test_language_tags():
+    tokenizer = mosers.MosesTokenizer("en-US")
+
+    tkns = tokenizer.tokenize("Mr. Smith isn't here.")
+    assert ["Mr.", "Smith", "isn", "&apos;t", "here", "."] == tkns
@@ -1,49 +1,92 @@
-use mosers::Language;
+use ::mosers::{Language, MosesTokenizerOptions};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
-use std::str::FromStr;
-use strum::ParseError;
+
+fn to_py_err(err: ::mosers::Error) -> PyErr {
+    match err {
+        ::mosers::Error::UnknownLanguage { .. } => PyValueError::new_err(err.to_string()),
+        ::mosers::Error::Regex(_) => PyRuntimeError::new_err(err.to_string()),
+        _ => PyRuntimeError::new_err(err.to_string()),
+    }
+}
 
 #[pyclass]
-struct MosesTokenizer(mosers::MosesTokenizer);
+struct MosesTokenizer {
+    tokenizer: ::mosers::MosesTokenizer,
+    return_str: bool,
+}
 
 #[pymethods]
 impl MosesTokenizer {
     #[new]
-    fn new(lang: String) -> PyResult<Self> {
-        let language: Result<Language, ParseError> = Language::from_str(&*lang);
-        let language = language.map_err(|_| pyo3::exceptions::PyValueError::new_err(""));
-        Ok(MosesTokenizer(mosers::MosesTokenizer::new(language?)))
+    #[pyo3(signature = (
+        lang,
+        *,
+        aggressive_dash_splits=None,
+        intratoken_slashes=None,
+        escape=None,
+        return_str=None,
+        penn=None,
+        penn_bracket_symbols=None,
+        split_cjk_chars=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        lang: String,
+        aggressive_dash_splits: Option<bool>,
+        intratoken_slashes: Option<bool>,
+        escape: Option<bool>,
+        return_str: Option<bool>,
+        penn: Option<bool>,
+        penn_bracket_symbols: Option<bool>,
+        split_cjk_chars: Option<bool>,
+    ) -> PyResult<Self> {
+        let language = Language::from_tag(&lang).map_err(to_py_err)?;
+        let defaults = MosesTokenizerOptions::default();
+        let tokenizer = ::mosers::MosesTokenizer::builder(language)
+            .options(MosesTokenizerOptions {
+                aggressive_dash_splits: aggressive_dash_splits
+                    .unwrap_or(defaults.aggressive_dash_splits),
+                intratoken_slashes: intratoken_slashes.unwrap_or(defaults.intratoken_slashes),
+                escape: escape.unwrap_or(defaults.escape),
+                penn: penn.unwrap_or(defaults.penn),
+                penn_bracket_symbols: penn_bracket_symbols.unwrap_or(defaults.penn_bracket_symbols),
+                split_cjk_chars: split_cjk_chars.unwrap_or(defaults.split_cjk_chars),
+            })
+            .build()
+            .map_err(to_py_err)?;
+
+        Ok(MosesTokenizer {
+            tokenizer,
+            return_str: return_str.unwrap_or(false),
+        })
     }
 
     fn tokenize(
         self_: PyRefMut<'_, Self>,
         text: String,
         escape: Option<bool>,
-    ) -> PyResult<Vec<String>> {
-        let tokens = self_.0.tokenize(text, escape);
-        let tokens: Vec<String> = tokens
-            .tokens()
-            .iter()
-            .map(|token| (*token).into())
-            .collect();
-
-        Ok(tokens)
+        return_str: Option<bool>,
+    ) -> PyResult<PyObject> {
+        let py = self_.py();
+        let tokens = self_.tokenizer.tokenize(text, escape);
+        if return_str.unwrap_or(self_.return_str) {
+            return Ok(tokens.into_string().into_py(py));
+        }
+        Ok(tokens.into_strings().into_py(py))
     }
 
-    fn penn_tokenize(self_: PyRefMut<'_, Self>, text: String) -> PyResult<Vec<String>> {
-        let tokens = self_.0.penn_tokenize(text);
-        let tokens: Vec<String> = tokens
-            .tokens()
-            .iter()
-            .map(|token| (*token).into())
-            .collect();
-
-        Ok(tokens)
+    fn penn_tokenize(
+        self_: PyRefMut<'_, Self>,
+        text: String,
+        escape: Option<bool>,
+    ) -> PyResult<Vec<String>> {
+        Ok(self_.tokenizer.penn_tokenize(text, escape).into_strings())
     }
 }
 
 #[pyclass]
-struct MosesPunctNormalizer(mosers::MosesPunctNormalizer);
+struct MosesPunctNormalizer(::mosers::MosesPunctNormalizer);
 
 #[pymethods]
 impl MosesPunctNormalizer {
@@ -56,11 +99,10 @@ impl MosesPunctNormalizer {
         pre_replace_unicode_punct: Option<bool>,
         post_remove_control_chars: Option<bool>,
     ) -> PyResult<Self> {
-        let language: Result<Language, ParseError> = Language::from_str(&*lang);
-        let language = language.map_err(|_| pyo3::exceptions::PyValueError::new_err(""));
+        let language = Language::from_tag(&lang).map_err(to_py_err)?;
         Ok(MosesPunctNormalizer(
-            mosers::MosesPunctNormalizer::new_with_options(
-                language?,
+            ::mosers::MosesPunctNormalizer::new_with_options(
+                language,
                 penn,
                 norm_quote_commas,
                 norm_numbers,
@@ -77,9 +119,15 @@ impl MosesPunctNormalizer {
     }
 }
 
+#[pyfunction]
+fn unescape_xml(text: String) -> PyResult<String> {
+    Ok(::mosers::unescape_xml(&text).into_owned())
+}
+
 #[pymodule]
 fn mosers(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<MosesTokenizer>()?;
     m.add_class::<MosesPunctNormalizer>()?;
+    m.add_function(wrap_pyfunction!(unescape_xml, m)?)?;
     Ok(())
 }